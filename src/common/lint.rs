@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+/// warnings that can be turned off or into errors by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    Unused,
+    Shadowing,
+    Unreachable,
+    TruncatingLiteral,
+}
+
+impl Lint {
+    pub const ALL: [Self; 4] = [
+        Self::Unused,
+        Self::Shadowing,
+        Self::Unreachable,
+        Self::TruncatingLiteral,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unused => "unused",
+            Self::Shadowing => "shadowing",
+            Self::Unreachable => "unreachable",
+            Self::TruncatingLiteral => "truncating_literal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.name() == name)
+    }
+
+    pub fn default_level(&self) -> LintLevel {
+        match self {
+            // shadowing on purpose is normal, so this is opt in
+            Self::Shadowing => LintLevel::Allow,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    /// the attribute (`#[allow(..)]`) or flag (`-A`) that sets this level
+    pub fn from_attr(name: &str) -> Option<Self> {
+        Some(match name {
+            "allow" => Self::Allow,
+            "warn" => Self::Warn,
+            "deny" => Self::Deny,
+            _ => return None,
+        })
+    }
+
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        Some(match flag {
+            "-A" => Self::Allow,
+            "-W" => Self::Warn,
+            "-D" => Self::Deny,
+            _ => return None,
+        })
+    }
+}
+
+/// levels set on an item or the items around it; these win over the command line
+#[derive(Debug, Clone, Default)]
+pub struct LintLevels(HashMap<Lint, LintLevel>);
+
+impl LintLevels {
+    pub fn get(&self, lint: Lint) -> Option<LintLevel> {
+        self.0.get(&lint).copied()
+    }
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.0.insert(lint, level);
+    }
+}
+
+/// levels from the command line
+#[derive(Debug, Default)]
+pub struct LintConfig {
+    pub levels: LintLevels,
+    /// `-D warnings` / `-A warnings`; applies to whatever would otherwise warn
+    pub warnings: Option<LintLevel>,
+}
+
+impl LintConfig {
+    /// parses `-W name`, `-D name` and `-A name` (or `-Wname`), leaving other args alone
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(level) = arg.get(..2).and_then(LintLevel::from_flag) else {
+                continue;
+            };
+            let name = match &arg[2..] {
+                "" => args
+                    .next()
+                    .ok_or_else(|| format!("expected a lint name after {arg}"))?,
+                name => name,
+            };
+            if name == "warnings" {
+                config.warnings = Some(level);
+                continue;
+            }
+            let lint = Lint::from_name(name).ok_or_else(|| format!("unknown lint '{name}'"))?;
+            config.levels.set(lint, level);
+        }
+        Ok(config)
+    }
+
+    pub fn level(&self, lint: Lint, item: &LintLevels) -> LintLevel {
+        let level = item
+            .get(lint)
+            .or(self.levels.get(lint))
+            .unwrap_or(lint.default_level());
+        match (level, self.warnings) {
+            (LintLevel::Warn, Some(w)) => w,
+            (level, _) => level,
+        }
+    }
+}
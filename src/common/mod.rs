@@ -1,5 +1,7 @@
 mod output;
 mod file;
+mod lint;
 
 pub use output::*;
 pub use file::*;
+pub use lint::*;
@@ -1,4 +1,4 @@
-use super::{FileMap, FilePos, FileSpan};
+use super::{FileMap, FilePos, FileSpan, Lint, LintConfig, LintLevel, LintLevels};
 
 #[derive(Debug, Clone)]
 pub struct CompilerMsg {
@@ -9,7 +9,9 @@ pub struct CompilerMsg {
 pub struct CompilerOutput {
     pub file_map: FileMap,
     pub errs: Vec<CompilerMsg>,
+    pub warnings: Vec<CompilerMsg>,
     pub hints: Vec<CompilerMsg>,
+    pub lints: LintConfig,
 }
 
 impl CompilerMsg {
@@ -52,13 +54,27 @@ impl CompilerOutput {
     pub fn new() -> Self {
         Self {
             errs: Vec::new(),
+            warnings: Vec::new(),
             hints: Vec::new(),
+            lints: LintConfig::default(),
             file_map: FileMap::new(),
         }
     }
     pub fn err(&mut self, msg: CompilerMsg) {
         self.errs.push(msg);
     }
+    pub fn warn(&mut self, msg: CompilerMsg) {
+        self.warnings.push(msg);
+    }
+    /// reports `msg` as a warning or error, or not at all, depending on the lint's level
+    pub fn lint(&mut self, lint: Lint, item: &LintLevels, mut msg: CompilerMsg) {
+        msg.msg = format!("{} [{}]", msg.msg, lint.name());
+        match self.lints.level(lint, item) {
+            LintLevel::Allow => (),
+            LintLevel::Warn => self.warn(msg),
+            LintLevel::Deny => self.err(msg),
+        }
+    }
     pub fn hint(&mut self, msg: CompilerMsg) {
         self.hints.push(msg);
     }
@@ -66,6 +82,9 @@ impl CompilerOutput {
         for err in &self.errs {
             err.write_to("error", out, &self.file_map).unwrap();
         }
+        for warning in &self.warnings {
+            warning.write_to("warning", out, &self.file_map).unwrap();
+        }
         for hint in &self.hints {
             hint.write_to("hint", out, &self.file_map).unwrap();
         }
@@ -52,6 +52,11 @@ pub enum LinkerInstruction<R = Reg, S = Symbol> {
         dest: R,
         offset: i32,
     },
+    Jalr {
+        dest: R,
+        offset: i32,
+        base: R,
+    },
     Call(S),
     J(S),
     Branch {
@@ -138,6 +143,15 @@ impl<R, S> LinkerInstruction<R, S> {
                 imm,
             },
             Self::Ret => I::Ret,
+            &Self::Jalr {
+                ref dest,
+                offset,
+                ref base,
+            } => I::Jalr {
+                dest: r(dest)?,
+                offset,
+                base: r(base)?,
+            },
             Self::Call(..) => todo!(),
             Self::Jal { .. } => todo!(),
             Self::J(..) => todo!(),
@@ -211,6 +225,7 @@ impl Instr for LinkerInstruction {
                 }
             }
             Self::Jal { dest, offset } => jal(*dest, BitsI32::new(*offset)),
+            Self::Jalr { dest, offset, base } => jalr(*dest, BitsI32::new(*offset), *base),
             Self::J(sym) => {
                 if let Some(addr) = sym_map.get(*sym) {
                     let offset = addr.val() as i32 - pos.val() as i32;
@@ -337,6 +352,7 @@ impl<R: std::fmt::Debug, S: std::fmt::Debug> LabeledFmt<S> for LinkerInstruction
                 imm,
             } => write!(f, "{}i {dest:?}, {src:?}, {imm}", opstr(*op, *funct)),
             Self::Jal { dest, offset } => write!(f, "jal {dest:?}, {offset:?}"),
+            Self::Jalr { dest, offset, base } => write!(f, "jalr {dest:?}, {offset}({base:?})"),
             Self::Call(s) => {
                 write!(f, "call ")?;
                 label(f, s)
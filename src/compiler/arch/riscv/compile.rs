@@ -2,13 +2,27 @@ use std::collections::HashMap;
 
 use crate::{
     compiler::{arch::riscv::Reg, debug::DebugInfo, UnlinkedFunction, UnlinkedProgram},
-    ir::{arch::riscv64::RegRef, LInstruction as IRI, LProgram, Len, Size, VarID},
+    ir::{
+        arch::riscv64::RegRef, BinOp, BoundsCheck, Entry, IntTy, LInstruction as IRI, LProgram,
+        Len, Size, Symbol, VarID, SLICE_LEN_OFFSET, SLICE_PTR_OFFSET,
+    },
 };
 
 use super::{LinkerInstruction as LI, *};
 
-fn align(s: &Size) -> i32 {
-    (*s as i32 - 1).div_euclid(8) + 1
+// byte offsets of the parts of a slice
+const SLICE_PTR: i32 = (SLICE_PTR_OFFSET / 8) as i32;
+const SLICE_LEN: i32 = (SLICE_LEN_OFFSET / 8) as i32;
+
+/// bits to bytes
+fn bytes(s: &Size) -> i32 {
+    s.div_ceil(8) as i32
+}
+
+/// how much room a var, arg or return value gets on the stack; every slot starts on a word
+/// so anything in it is aligned, including the fields inside structs
+fn slot(s: &Size) -> i32 {
+    s.div_ceil(8).next_multiple_of(8) as i32
 }
 
 fn mov_mem(
@@ -44,6 +58,187 @@ fn mov_mem(
     }
 }
 
+/// the load width for an int of `bits`; stores only have the signed widths
+fn int_width(bits: u32, signed: bool) -> Funct3 {
+    match (bits, signed) {
+        (8, true) => width::B,
+        (8, false) => width::BU,
+        (16, true) => width::H,
+        (16, false) => width::HU,
+        (32, true) => width::W,
+        (32, false) => width::WU,
+        _ => width::D,
+    }
+}
+
+/// stores the low `int.bits` of a register
+fn store_int(src: Reg, offset: i32, base: Reg, int: IntTy) -> LI {
+    LI::Store {
+        width: int_width(int.bits, true),
+        src,
+        offset,
+        base,
+    }
+}
+
+/// loads an int into a register, filling the rest with copies of its sign bit if it's signed
+/// and with zeroes if it isn't
+fn load_int(dest: Reg, offset: i32, base: Reg, int: IntTy) -> LI {
+    LI::Load {
+        width: int_width(int.bits, int.signed),
+        dest,
+        offset,
+        base,
+    }
+}
+
+/// `_start`: the kernel leaves `argc`, then `argv` and `envp` (both null terminated)
+/// on the stack; main gets them as slices, and its return value becomes the exit status
+fn start_shim(entry: Entry) -> UnlinkedFunction<LI> {
+    let [env_loop, env_end] = entry.labels;
+    let mut v = vec![
+        // a0 = argc, a1 = argv, a2 = envp
+        LI::ld(a0, 0, sp),
+        LI::addi(a1, sp, 8),
+        LI::OpImmF7 {
+            op: op32i::SL,
+            funct: op32i::LOGICAL,
+            dest: t0,
+            src: a0,
+            imm: 3,
+        },
+        LI::Op {
+            op: op32i::ADD,
+            funct: op32i::F7ADD,
+            dest: a2,
+            src1: a1,
+            src2: t0,
+        },
+        LI::addi(a2, a2, 8),
+        // a3 = number of env vars
+        LI::Mv { dest: t0, src: a2 },
+    ];
+    let mut locations = HashMap::new();
+    locations.insert(v.len(), env_loop);
+    v.extend([
+        LI::ld(t1, 0, t0),
+        LI::Branch {
+            to: env_end,
+            typ: branch::EQ,
+            left: t1,
+            right: zero,
+        },
+        LI::addi(t0, t0, 8),
+        LI::J(env_loop),
+    ]);
+    locations.insert(v.len(), env_end);
+    v.extend([
+        LI::Op {
+            op: op32i::ADD,
+            funct: op32i::F7SUB,
+            dest: a3,
+            src1: t0,
+            src2: a2,
+        },
+        LI::OpImmF7 {
+            op: op32i::SR,
+            funct: op32i::LOGICAL,
+            dest: a3,
+            src: a3,
+            imm: 3,
+        },
+        // room for the return value, keeping sp 16 byte aligned
+        LI::addi(sp, sp, -16),
+        LI::sd(zero, 0, sp),
+    ]);
+    // same layout a call site uses: return address slot, then args going down
+    let mut offset = 0;
+    if entry.ret > 0 {
+        offset -= 8;
+        v.push(LI::sd(sp, offset, sp));
+    }
+    if entry.args {
+        for (ptr, len) in [(a1, a0), (a2, a3)] {
+            offset -= 16;
+            v.push(LI::sd(ptr, offset + SLICE_PTR, sp));
+            v.push(LI::sd(len, offset + SLICE_LEN, sp));
+        }
+    }
+    v.extend([
+        LI::Call(entry.main),
+        LI::ld(a0, 0, sp),
+        LI::Li { dest: a7, imm: 93 },
+        LI::ECall,
+    ]);
+    UnlinkedFunction {
+        instrs: v,
+        sym: entry.start,
+        locations,
+    }
+}
+
+/// puts the start of the elements of the array or slice at `src` in `dest`
+fn elems_addr(v: &mut Vec<LI>, dest: Reg, src: i32, slice: bool) {
+    if slice {
+        v.push(LI::ld(dest, src + SLICE_PTR, sp));
+    } else {
+        v.push(LI::addi(dest, sp, src));
+    }
+}
+
+fn load_len(v: &mut Vec<LI>, dest: Reg, src: i32, len: Option<Len>) {
+    match len {
+        Some(len) => v.push(LI::Li {
+            dest,
+            imm: len as i32,
+        }),
+        None => v.push(LI::ld(dest, src + SLICE_LEN, sp)),
+    }
+}
+
+/// continues at `check.ok`; jumping to `check.fail` prints the message and exits with 101
+fn bounds_fail(v: &mut Vec<LI>, locations: &mut HashMap<usize, Symbol>, check: &BoundsCheck) {
+    v.push(LI::J(check.ok));
+    locations.insert(v.len(), check.fail);
+    v.extend([
+        LI::La {
+            dest: a1,
+            src: check.msg,
+        },
+        LI::Li {
+            dest: a2,
+            imm: check.msg_len as i32,
+        },
+        LI::Li { dest: a0, imm: 2 },
+        LI::Li { dest: a7, imm: 64 },
+        LI::ECall,
+        LI::Li { dest: a0, imm: 101 },
+        LI::Li { dest: a7, imm: 93 },
+        LI::ECall,
+    ]);
+    locations.insert(v.len(), check.ok);
+}
+
+fn mul(dest: Reg, src1: Reg, src2: Reg) -> LI {
+    LI::Op {
+        op: op32m::MUL,
+        funct: op32m::FUNCT7,
+        dest,
+        src1,
+        src2,
+    }
+}
+
+fn add(dest: Reg, src1: Reg, src2: Reg) -> LI {
+    LI::Op {
+        op: op32i::ADD,
+        funct: op32i::F7ADD,
+        dest,
+        src1,
+        src2,
+    }
+}
+
 pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
     let mut fns = Vec::new();
     let mut data = Vec::new();
@@ -64,20 +259,20 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
         }
         for (id, s) in &f.stack {
             stack.insert(id, stack_len);
-            stack_len += align(s);
+            stack_len += slot(s);
         }
         for (id, s) in f.args.iter().rev() {
             stack.insert(id, stack_len);
-            stack_len += align(s);
+            stack_len += slot(s);
         }
         if f.ret_size > 0 {
+            // where to write the return value, which the caller puts here
             stack_rva = Some(stack_len);
-            stack_len += align(&f.ret_size);
+            stack_len += 8;
         }
         v.push(LI::addi(sp, sp, -stack_len));
         for (id, var) in &f.subvar_map {
-            // TODO: ALIGN DOES NOT MAKE SENSE HERE!!! need to choose to decide in lower or asm
-            stack.insert(id, stack[&var.id] + align(&var.offset));
+            stack.insert(id, stack[&var.id] + bytes(&var.offset));
         }
         let has_stack = stack_len > 0;
         if has_stack {
@@ -104,22 +299,39 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
                     dst_offset: dest_offset,
                     src,
                     src_offset,
+                    size,
                 } => {
-                    let s = align(&f.stack[src]) as u32;
                     mov_mem(
                         &mut v,
                         sp,
-                        stack[src] + align(src_offset),
+                        stack[src] + bytes(src_offset),
                         sp,
-                        stack[dest] + align(dest_offset),
+                        stack[dest] + bytes(dest_offset),
                         t0,
-                        s,
+                        bytes(size) as Len,
                     );
                 }
                 IRI::Ref { dst: dest, src } => {
                     v.push(LI::addi(t0, sp, stack[src]));
                     v.push(LI::sd(t0, stack[dest], sp));
                 }
+                IRI::Load {
+                    dst: dest,
+                    src,
+                    size,
+                } => {
+                    v.push(LI::ld(t1, stack[src], sp));
+                    mov_mem(&mut v, t1, 0, sp, stack[dest], t0, *size);
+                }
+                IRI::Store {
+                    dst: dest,
+                    offset,
+                    src,
+                    size,
+                } => {
+                    v.push(LI::ld(t1, stack[dest], sp));
+                    mov_mem(&mut v, sp, stack[src], t1, *offset as i32, t0, *size);
+                }
                 IRI::LoadAddr {
                     dst: dest,
                     offset,
@@ -147,25 +359,64 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
                 }
                 IRI::Call { dst: dest, f, args } => {
                     let mut offset = 0;
-                    if let Some((dest, s)) = dest {
-                        offset -= align(s);
+                    if let Some((dest, _)) = dest {
+                        offset -= 8;
                         v.push(LI::addi(t0, sp, stack[&dest]));
                         v.push(LI::sd(t0, offset, sp))
                     }
                     for (arg, s) in args {
-                        let bs = align(s);
-                        offset -= bs;
-                        mov_mem(&mut v, sp, stack[arg], sp, offset, t0, bs as Len);
+                        offset -= slot(s);
+                        mov_mem(&mut v, sp, stack[arg], sp, offset, t0, bytes(s) as Len);
                     }
                     v.push(LI::Call(*f));
                 }
+                IRI::CallDyn {
+                    dst: dest,
+                    obj,
+                    idx,
+                    args,
+                } => {
+                    let mut offset = 0;
+                    if let Some((dest, _)) = dest {
+                        offset -= 8;
+                        v.push(LI::addi(t0, sp, stack[&dest]));
+                        v.push(LI::sd(t0, offset, sp))
+                    }
+                    // receiver: the data half of the fat pointer
+                    offset -= 8;
+                    mov_mem(&mut v, sp, stack[obj], sp, offset, t0, 8);
+                    for (arg, s) in args {
+                        offset -= slot(s);
+                        mov_mem(&mut v, sp, stack[arg], sp, offset, t0, bytes(s) as Len);
+                    }
+                    // vtable entries are offsets from the vtable itself
+                    v.extend([
+                        LI::ld(t1, stack[obj] + 8, sp),
+                        LI::ld(t2, *idx as i32 * 8, t1),
+                        LI::Op {
+                            op: op32i::ADD,
+                            funct: op32i::F7ADD,
+                            dest: t2,
+                            src1: t1,
+                            src2: t2,
+                        },
+                        LI::Jalr {
+                            dest: ra,
+                            offset: 0,
+                            base: t2,
+                        },
+                    ]);
+                }
                 IRI::AsmBlock {
                     inputs,
                     outputs,
                     instructions,
                 } => {
-                    for (reg, var) in inputs {
-                        v.push(LI::ld(*reg, stack[var], sp));
+                    for (reg, var, int) in inputs {
+                        match int {
+                            Some(int) => v.push(load_int(*reg, stack[var], sp, *int)),
+                            None => v.push(LI::ld(*reg, stack[var], sp)),
+                        }
                     }
                     fn r(rr: &RegRef<VarID>) -> Reg {
                         match rr {
@@ -176,8 +427,11 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
                     for i in instructions {
                         v.push(i.map(|v| r(v)));
                     }
-                    for (reg, var) in outputs {
-                        v.push(LI::sd(*reg, stack[var], sp));
+                    for (reg, var, int) in outputs {
+                        match int {
+                            Some(int) => v.push(store_int(*reg, stack[var], sp, *int)),
+                            None => v.push(LI::sd(*reg, stack[var], sp)),
+                        }
                     }
                 }
                 IRI::Ret { src } => {
@@ -186,15 +440,174 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
                             panic!("no return value address on stack!")
                         };
                         v.push(LI::ld(t0, rva, sp));
-                        mov_mem(&mut v, sp, stack[src], t0, 0, t1, align(&f.ret_size) as u32);
+                        mov_mem(&mut v, sp, stack[src], t0, 0, t1, bytes(&f.ret_size) as Len);
                     }
                     v.extend(&ret);
                 }
+                IRI::Index {
+                    dst,
+                    src,
+                    idx,
+                    size,
+                    len,
+                    check,
+                } => {
+                    // negative indices wrap around to huge ones and fail the check
+                    let (idx, int) = idx;
+                    v.push(load_int(t1, stack[idx], sp, *int));
+                    load_len(&mut v, t2, stack[src], *len);
+                    v.push(LI::Branch {
+                        to: check.fail,
+                        typ: branch::GEU,
+                        left: t1,
+                        right: t2,
+                    });
+                    bounds_fail(&mut v, &mut locations, check);
+                    elems_addr(&mut v, t0, stack[src], len.is_none());
+                    v.extend([
+                        LI::Li {
+                            dest: t2,
+                            imm: *size as i32,
+                        },
+                        mul(t1, t1, t2),
+                        add(t0, t0, t1),
+                        LI::sd(t0, stack[dst], sp),
+                    ]);
+                }
+                IRI::SliceRange {
+                    dst,
+                    src,
+                    start,
+                    end,
+                    size,
+                    len,
+                    check,
+                } => {
+                    match start {
+                        Some((start, int)) => v.push(load_int(t1, stack[start], sp, *int)),
+                        None => v.push(LI::Li { dest: t1, imm: 0 }),
+                    }
+                    load_len(&mut v, t2, stack[src], *len);
+                    match end {
+                        Some((end, int)) => v.push(load_int(t3, stack[end], sp, *int)),
+                        None => v.push(LI::Mv { dest: t3, src: t2 }),
+                    }
+                    // fails if len < end or end < start
+                    v.extend([
+                        LI::Branch {
+                            to: check.fail,
+                            typ: branch::LTU,
+                            left: t2,
+                            right: t3,
+                        },
+                        LI::Branch {
+                            to: check.fail,
+                            typ: branch::LTU,
+                            left: t3,
+                            right: t1,
+                        },
+                    ]);
+                    bounds_fail(&mut v, &mut locations, check);
+                    elems_addr(&mut v, t0, stack[src], len.is_none());
+                    v.extend([
+                        LI::Li {
+                            dest: t2,
+                            imm: *size as i32,
+                        },
+                        mul(t2, t1, t2),
+                        add(t0, t0, t2),
+                        LI::sd(t0, stack[dst] + SLICE_PTR, sp),
+                        LI::Op {
+                            op: op32i::ADD,
+                            funct: op32i::F7SUB,
+                            dest: t3,
+                            src1: t3,
+                            src2: t1,
+                        },
+                        LI::sd(t3, stack[dst] + SLICE_LEN, sp),
+                    ]);
+                }
+                IRI::BinOp {
+                    dst,
+                    op,
+                    lhs,
+                    rhs,
+                    int,
+                } => {
+                    v.push(load_int(t0, stack[lhs], sp, *int));
+                    v.push(load_int(t1, stack[rhs], sp, *int));
+                    let (f3, funct, src1, src2) = match (op, int.signed) {
+                        (BinOp::Add, _) => (op32i::ADD, op32i::F7ADD, t0, t1),
+                        (BinOp::Sub, _) => (op32i::ADD, op32i::F7SUB, t0, t1),
+                        (BinOp::Mul, _) => (op32m::MUL, op32m::FUNCT7, t0, t1),
+                        (BinOp::Div, true) => (op32m::DIV, op32m::FUNCT7, t0, t1),
+                        (BinOp::Div, false) => (op32m::DIVU, op32m::FUNCT7, t0, t1),
+                        (BinOp::Lt, true) => (op32i::SLT, op32i::FUNCT7, t0, t1),
+                        (BinOp::Lt, false) => (op32i::SLTU, op32i::FUNCT7, t0, t1),
+                        (BinOp::Gt, true) => (op32i::SLT, op32i::FUNCT7, t1, t0),
+                        (BinOp::Gt, false) => (op32i::SLTU, op32i::FUNCT7, t1, t0),
+                        // `a <= b` is `!(b < a)` and `a >= b` is `!(a < b)`
+                        (BinOp::Le, true) => (op32i::SLT, op32i::FUNCT7, t1, t0),
+                        (BinOp::Le, false) => (op32i::SLTU, op32i::FUNCT7, t1, t0),
+                        (BinOp::Ge, true) => (op32i::SLT, op32i::FUNCT7, t0, t1),
+                        (BinOp::Ge, false) => (op32i::SLTU, op32i::FUNCT7, t0, t1),
+                        // equality looks at `a - b`
+                        (BinOp::Eq | BinOp::Ne, _) => (op32i::ADD, op32i::F7SUB, t0, t1),
+                    };
+                    v.push(LI::Op {
+                        op: f3,
+                        funct,
+                        dest: t0,
+                        src1,
+                        src2,
+                    });
+                    match op {
+                        BinOp::Le | BinOp::Ge => v.push(LI::OpImm {
+                            op: op32i::XOR,
+                            dest: t0,
+                            src: t0,
+                            imm: 1,
+                        }),
+                        // seqz
+                        BinOp::Eq => v.push(LI::OpImm {
+                            op: op32i::SLTU,
+                            dest: t0,
+                            src: t0,
+                            imm: 1,
+                        }),
+                        // snez
+                        BinOp::Ne => v.push(LI::Op {
+                            op: op32i::SLTU,
+                            funct: op32i::FUNCT7,
+                            dest: t0,
+                            src1: zero,
+                            src2: t0,
+                        }),
+                        _ => (),
+                    }
+                    // the high bits of a narrow result are cut off by the store
+                    let bits = if op.is_cmp() { 8 } else { int.bits };
+                    v.push(LI::Store {
+                        width: int_width(bits, true),
+                        src: t0,
+                        offset: stack[dst],
+                        base: sp,
+                    });
+                }
+                IRI::Cast { dst, src, from, to } => {
+                    v.push(load_int(t0, stack[src], sp, *from));
+                    v.push(store_int(t0, stack[dst], sp, *to));
+                }
                 IRI::Jump(location) => {
                     v.push(LI::J(*location));
                 }
                 IRI::Branch { to, cond } => {
-                    v.push(LI::ld(t0, stack[cond], sp));
+                    v.push(LI::Load {
+                        width: width::BU,
+                        dest: t0,
+                        offset: stack[cond],
+                        base: sp,
+                    });
                     v.push(LI::Branch {
                         to: *to,
                         typ: branch::EQ,
@@ -202,6 +615,37 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
                         right: zero,
                     })
                 }
+                IRI::BranchCmp {
+                    to,
+                    op,
+                    lhs,
+                    rhs,
+                    int,
+                } => {
+                    v.push(load_int(t0, stack[lhs], sp, *int));
+                    v.push(load_int(t1, stack[rhs], sp, *int));
+                    // skips when the comparison is false, so each op takes the opposite
+                    // branch: `a < b` fails when `a >= b`, `a > b` when `b >= a`, and so on
+                    let (ge, lt) = match int.signed {
+                        true => (branch::GE, branch::LT),
+                        false => (branch::GEU, branch::LTU),
+                    };
+                    let (typ, left, right) = match op {
+                        BinOp::Lt => (ge, t0, t1),
+                        BinOp::Gt => (ge, t1, t0),
+                        BinOp::Le => (lt, t1, t0),
+                        BinOp::Ge => (lt, t0, t1),
+                        BinOp::Eq => (branch::NE, t0, t1),
+                        BinOp::Ne => (branch::EQ, t0, t1),
+                        _ => unreachable!("only comparisons are fused into branches"),
+                    };
+                    v.push(LI::Branch {
+                        to: *to,
+                        typ,
+                        left,
+                        right,
+                    })
+                }
                 IRI::Mark(location) => {
                     locations.insert(v.len(), *location);
                 }
@@ -214,10 +658,14 @@ pub fn compile(program: &LProgram) -> UnlinkedProgram<LI> {
             locations,
         });
     }
+    let entry = program.entry();
+    dbg.push_fn(Vec::new());
+    fns.push(start_shim(entry));
     UnlinkedProgram {
         fns,
         ro_data: data,
-        start: Some(program.entry()),
+        relocs: program.relocs().to_vec(),
+        start: Some(entry.start),
         dbg,
         sym_count: program.len(),
     }
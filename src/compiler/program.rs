@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    ir::Symbol,
+    ir::{DataReloc, Symbol},
     util::{Labelable, LabeledFmt},
 };
 
@@ -15,6 +15,7 @@ pub struct LinkedProgram {
 pub struct UnlinkedProgram<I: Instr> {
     pub fns: Vec<UnlinkedFunction<I>>,
     pub ro_data: Vec<(Vec<u8>, Symbol)>,
+    pub relocs: Vec<DataReloc>,
     pub sym_count: usize,
     pub start: Option<Symbol>,
     pub dbg: DebugInfo,
@@ -65,6 +66,13 @@ impl<I: Instr + std::fmt::Debug> UnlinkedProgram<I> {
             }
         }
         assert!(missing.is_empty());
+        for r in self.relocs {
+            let base = sym_table.get(r.data).expect("reloc in unknown data");
+            let target = sym_table.get(r.target).expect("reloc to unknown symbol");
+            let pos = (base.val() + r.offset as u64) as usize;
+            let rel = target.val() as i64 - base.val() as i64;
+            data[pos..pos + 8].copy_from_slice(&rel.to_le_bytes());
+        }
         LinkedProgram {
             code: data,
             start: self
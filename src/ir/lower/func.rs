@@ -15,16 +15,31 @@ pub struct IRLFunction {
 
 #[derive(Debug)]
 pub enum LInstruction {
+    /// copies `size` bits; offsets are in bits too
     Mv {
         dst: VarID,
         dst_offset: Size,
         src: VarID,
         src_offset: Size,
+        size: Size,
     },
     Ref {
         dst: VarID,
         src: VarID,
     },
+    /// `dst` gets `size` bytes from the address in `src`
+    Load {
+        dst: VarID,
+        src: VarID,
+        size: Len,
+    },
+    /// the `size` bytes of `src` are written `offset` bytes past the address in `dst`
+    Store {
+        dst: VarID,
+        offset: Len,
+        src: VarID,
+        size: Len,
+    },
     LoadAddr {
         dst: VarID,
         offset: Size,
@@ -41,24 +56,90 @@ pub enum LInstruction {
         f: Symbol,
         args: Vec<(VarID, Size)>,
     },
+    /// call through the vtable of the trait object `obj`;
+    /// its data pointer is passed before `args`
+    CallDyn {
+        dst: Option<(VarID, Size)>,
+        obj: VarID,
+        idx: usize,
+        args: Vec<(VarID, Size)>,
+    },
+    /// inputs that are ints narrower than a register are sign or zero extended to fill it
     AsmBlock {
         instructions: Vec<RV64Instruction<VarID>>,
-        inputs: Vec<(Reg, VarID)>,
-        outputs: Vec<(Reg, VarID)>,
+        inputs: Vec<(Reg, VarID, Option<IntTy>)>,
+        outputs: Vec<(Reg, VarID, Option<IntTy>)>,
     },
     Ret {
         src: Option<VarID>,
     },
+    /// `dst` gets the address of element `idx` in `src`, whose elements are `size` bytes;
+    /// `len` is the length of an array, or None if `src` is a slice; the index is an int
+    /// that's extended to a full register when loaded
+    Index {
+        dst: VarID,
+        src: VarID,
+        idx: (VarID, IntTy),
+        size: Len,
+        len: Option<Len>,
+        check: BoundsCheck,
+    },
+    /// `dst` gets the slice `src[start..end]`; see `Index`
+    SliceRange {
+        dst: VarID,
+        src: VarID,
+        start: Option<(VarID, IntTy)>,
+        end: Option<(VarID, IntTy)>,
+        size: Len,
+        len: Option<Len>,
+        check: BoundsCheck,
+    },
+    /// both sides are `int`, extended to a full register when loaded; comparisons write
+    /// a bool to `dst` and everything else writes `int`
+    BinOp {
+        dst: VarID,
+        op: BinOp,
+        lhs: VarID,
+        rhs: VarID,
+        int: IntTy,
+    },
+    /// `src` is loaded as `from`, extending it if it's narrower than a register, and
+    /// stored as `to`, which cuts off the high bits if it's narrower than `from`
+    Cast {
+        dst: VarID,
+        src: VarID,
+        from: IntTy,
+        to: IntTy,
+    },
     // TODO I feel like this should be turned into control flow instructions, maybe...
     // not sure but LLVM has them so might be right play; seems optimal for optimization
     Jump(Symbol),
+    /// jumps to `to` if the bool `cond` is false
     Branch {
         to: Symbol,
         cond: VarID,
     },
+    /// jumps to `to` unless `lhs op rhs`; this is a comparison that only fed a branch,
+    /// so its result never has to be stored
+    BranchCmp {
+        to: Symbol,
+        op: BinOp,
+        lhs: VarID,
+        rhs: VarID,
+        int: IntTy,
+    },
     Mark(Symbol),
 }
 
+/// where a failed bounds check goes: print `msg` and abort
+#[derive(Debug, Clone, Copy)]
+pub struct BoundsCheck {
+    pub msg: Symbol,
+    pub msg_len: Len,
+    pub fail: Symbol,
+    pub ok: Symbol,
+}
+
 impl LInstruction {
     pub fn is_ret(&self) -> bool {
         matches!(self, Self::Ret { .. })
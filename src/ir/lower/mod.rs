@@ -1,9 +1,11 @@
 mod func;
+mod mono;
 mod program;
 mod symbol;
 mod res;
 
 pub use func::*;
+pub use mono::*;
 pub use program::*;
 pub use symbol::*;
 
@@ -0,0 +1,124 @@
+//! monomorphization support: generic args are kept as (type, env) pairs instead of
+//! building new types, so the lowering can stay on an immutable UProgram
+
+use std::{collections::HashMap, rc::Rc};
+
+use super::{clean_type, FnID, GenericID, Len, StructID, Type, TypeID, UProgram};
+
+/// what each generic of the current instance is bound to.
+/// bindings are evaluated in the env of whoever created the instance,
+/// so `T` in `fn a<T>() { b::<T>() }` chains back to the concrete type
+#[derive(Clone, Default)]
+pub struct GEnv(Option<Rc<HashMap<GenericID, (TypeID, GEnv)>>>);
+
+impl GEnv {
+    pub fn new(gargs: &[GenericID], args: &[TypeID], parent: &GEnv) -> Self {
+        if gargs.is_empty() {
+            return Self::default();
+        }
+        let map = gargs
+            .iter()
+            .zip(args)
+            .map(|(&g, &t)| (g, (t, parent.clone())))
+            .collect();
+        Self(Some(Rc::new(map)))
+    }
+
+    pub fn get(&self, id: GenericID) -> Option<(TypeID, &GEnv)> {
+        let (ty, env) = self.0.as_ref()?.get(&id)?;
+        Some((*ty, env))
+    }
+
+    /// follows generics until reaching a type that isn't one
+    pub fn resolve<'a>(&'a self, p: &UProgram, ty: TypeID) -> (TypeID, &'a GEnv) {
+        let mut ty = clean_type(&p.types, ty).unwrap_or(ty);
+        let mut env = self;
+        while let Type::Generic(g) = p.types[ty] {
+            let Some((next, next_env)) = env.get(g) else {
+                break;
+            };
+            ty = clean_type(&p.types, next).unwrap_or(next);
+            env = next_env;
+        }
+        (ty, env)
+    }
+}
+
+/// a type with its generics substituted; identifies instances, since the same
+/// concrete type can show up under many type ids (and many types share a name)
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum TypeKey {
+    Bits(u32),
+    Struct(StructID, Vec<TypeKey>),
+    FnInst(FnID, Vec<TypeKey>),
+    Ref(Box<TypeKey>),
+    Slice(Box<TypeKey>),
+    Array(Box<TypeKey>, Len),
+    Unit,
+    /// anything else has no generics inside, so its own id is enough
+    Other(TypeID),
+}
+
+/// a single instance of a (possibly generic) function
+pub struct FnInstance {
+    pub id: FnID,
+    pub env: GEnv,
+    /// generic args after substitution; empty if not generic
+    pub key: Vec<TypeKey>,
+    /// readable generic args after substitution, eg. `<64, slice<8>>`
+    pub suffix: String,
+}
+
+impl FnInstance {
+    pub fn plain(id: FnID) -> Self {
+        Self {
+            id,
+            env: GEnv::default(),
+            key: Vec::new(),
+            suffix: String::new(),
+        }
+    }
+
+    pub fn label(&self, p: &UProgram) -> String {
+        format!("{}{}", p.fns[self.id].name, self.suffix)
+    }
+}
+
+pub fn type_key(p: &UProgram, ty: TypeID, env: &GEnv) -> TypeKey {
+    let (ty, env) = env.resolve(p, ty);
+    match &p.types[ty] {
+        &Type::Bits(b) => TypeKey::Bits(b),
+        Type::Struct(si) => TypeKey::Struct(si.id, gargs_key(p, &si.gargs, env)),
+        Type::FnInst(fi) => TypeKey::FnInst(fi.id, gargs_key(p, &fi.gargs, env)),
+        &Type::Ref(t) => TypeKey::Ref(Box::new(type_key(p, t, env))),
+        &Type::Slice(t) => TypeKey::Slice(Box::new(type_key(p, t, env))),
+        &Type::Array(t, len) => TypeKey::Array(Box::new(type_key(p, t, env)), len),
+        Type::Unit => TypeKey::Unit,
+        _ => TypeKey::Other(ty),
+    }
+}
+
+pub fn gargs_key(p: &UProgram, args: &[TypeID], env: &GEnv) -> Vec<TypeKey> {
+    args.iter().map(|&a| type_key(p, a, env)).collect()
+}
+
+/// like `UProgram::type_name`, but with generics substituted
+pub fn type_name(p: &UProgram, ty: TypeID, env: &GEnv) -> String {
+    let (ty, env) = env.resolve(p, ty);
+    match &p.types[ty] {
+        Type::Struct(si) => format!("{}{}", p.structs[si.id].name, gargs_name(p, &si.gargs, env)),
+        Type::FnInst(fi) => format!("{}{}", p.fns[fi.id].name, gargs_name(p, &fi.gargs, env)),
+        &Type::Ref(t) => format!("{}&", type_name(p, t, env)),
+        &Type::Slice(t) => format!("&[{}]", type_name(p, t, env)),
+        &Type::Array(t, len) => format!("[{}; {len}]", type_name(p, t, env)),
+        _ => p.type_name(ty),
+    }
+}
+
+pub fn gargs_name(p: &UProgram, args: &[TypeID], env: &GEnv) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+    let args: Vec<_> = args.iter().map(|&a| type_name(p, a, env)).collect();
+    format!("<{}>", args.join(", "))
+}
@@ -1,30 +1,46 @@
 use std::collections::HashMap;
 
 use super::{
-    IRLFunction, LInstruction, Len, Symbol, SymbolSpaceBuilder, UInstruction, UProgram, VarID,
+    clean_type, gargs_key, gargs_name, BoundsCheck, DataID, FnInstance, GEnv, IRLFunction,
+    LInstruction, Len, Symbol, SymbolSpaceBuilder, TypeKey, UInstruction, UProgram, VarID,
 };
 use crate::ir::{
-    AsmBlockArgType, Size, StructInst, SymbolSpace, Type, TypeID, UFunc, UInstrInst, VarOffset,
+    is_temp, slice_field_offset, AsmBlockArgType, Attr, Callee, FnID, FnInst, ImplID, IntTy,
+    Member, MemberID, Size, StructID, StructInst, SymbolSpace, Type, TypeID, UFunc, UInstrInst,
+    VarOffset, DEREF_VAR, SLICE_LEN_OFFSET, SLICE_PTR_OFFSET,
 };
 
 pub struct LProgram {
     sym_space: SymbolSpace,
-    entry: Symbol,
+    entry: Entry,
 }
 
-// NOTE: there are THREE places here where I specify size (8)
+/// what the target's startup shim needs to call `main`
+#[derive(Clone, Copy)]
+pub struct Entry {
+    /// the shim itself, which is what the binary starts at
+    pub start: Symbol,
+    pub main: Symbol,
+    /// whether main takes `args` and `env` slices
+    pub args: bool,
+    /// size of main's return value, used as the exit status if nonzero
+    pub ret: Size,
+    /// spare symbols for branches inside the shim
+    pub labels: [Symbol; 2],
+}
 
 impl LProgram {
     pub fn create(p: &UProgram) -> Result<Self, String> {
-        let start = p
-            .names
-            .id::<UFunc>(&[], "crate")
-            .ok_or("no start method found")?;
-        let mut ssbuilder = SymbolSpaceBuilder::with_entries(&[start]);
-        let entry = ssbuilder.func(&start);
-        while let Some((sym, i)) = ssbuilder.pop_fn() {
-            let f = &p.fns[i.0];
-            let mut fbuilder = LFunctionBuilder::new(p, &mut ssbuilder);
+        let main = find_main(p)?;
+        let mut ssbuilder = SymbolSpaceBuilder::with_entries(&[main]);
+        let main_sym = ssbuilder.func(FnInstance::plain(main));
+        let start = ssbuilder.reserve();
+        let labels = [*ssbuilder.reserve(), *ssbuilder.reserve()];
+        let mut main_ret = 0;
+        while let Some((sym, inst)) = ssbuilder.pop_fn() {
+            let f = &p.fns[inst.id];
+            let label = inst.label(p);
+            let mut fbuilder = LFunctionBuilder::new(p, &mut ssbuilder, inst.env);
             for i in &f.instructions {
                 fbuilder.insert_instr(i);
             }
@@ -32,30 +48,94 @@ impl LProgram {
                 fbuilder.instrs.push(LInstruction::Ret { src: None });
             }
             let res = fbuilder.finish(f);
-            ssbuilder.write_fn(sym, res, Some(f.name.clone()));
+            if *sym == main_sym {
+                main_ret = res.ret_size;
+            }
+            ssbuilder.write_fn(sym, res, Some(label));
         }
+        let entry = Entry {
+            start: ssbuilder.write_fn_label(start, "_start".to_string()),
+            main: main_sym,
+            args: !p.fns[main].args.is_empty(),
+            ret: main_ret,
+            labels,
+        };
         let sym_space = ssbuilder.finish().expect("we failed the mission");
         Ok(Self { sym_space, entry })
     }
 
-    pub fn entry(&self) -> Symbol {
+    pub fn entry(&self) -> Entry {
         self.entry
     }
 }
 
+/// the fn marked `#[entry]` in the entry package, or else `main` in the entry module;
+/// it takes either nothing or `args` and `env` slices
+fn find_main(p: &UProgram) -> Result<FnID, String> {
+    let module = p.entry.ok_or("no entry module")?;
+    let mut entries = p
+        .fns
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.attrs.contains(&Attr::Entry) && p.in_entry_package(f.module));
+    let id = if let Some((id, _)) = entries.next() {
+        if let Some((_, f)) = entries.next() {
+            return Err(format!(
+                "more than one #[entry] function, including '{}'",
+                f.name
+            ));
+        }
+        FnID::from(id)
+    } else if let Some(Member {
+        id: MemberID::Fn(id),
+        ..
+    }) = p.modules[module].members.get("main")
+    {
+        *id
+    } else {
+        return Err(format!(
+            "no main function found in '{}'",
+            p.module_path(module)
+        ));
+    };
+    let f = &p.fns[id];
+    let name = &f.name;
+    if !f.gargs.is_empty() {
+        return Err(format!("{name} can't be generic"));
+    }
+    let slices = f
+        .args
+        .iter()
+        .all(|a| matches!(p.types[p.vars[*a].ty], Type::Slice(_)));
+    if !(f.args.is_empty() || f.args.len() == 2 && slices) {
+        return Err(format!(
+            "{name} must take no arguments or `args` and `env` slices"
+        ));
+    }
+    // the return value becomes the exit status
+    let ret = p.res_ty(f.ret).and_then(|t| clean_type(&p.types, t));
+    if !ret.is_some_and(|t| matches!(p.types[t], Type::Unit | Type::Bits(1..=64))) {
+        return Err(format!("{name} must return nothing or an integer"));
+    }
+    Ok(id)
+}
+
 pub struct LStructInst {
     offsets: Vec<Len>,
-    types: Vec<Type>,
+    types: Vec<TypeID>,
     order: HashMap<String, usize>,
     size: Size,
+    align: Size,
+    /// env the field types are in
+    env: GEnv,
 }
 
 impl LStructInst {
     pub fn offset(&self, name: &str) -> Option<Len> {
         Some(self.offsets[*self.order.get(name)?])
     }
-    pub fn ty(&self, name: &str) -> Option<&Type> {
-        Some(&self.types[*self.order.get(name)?])
+    pub fn ty(&self, name: &str) -> Option<(TypeID, &GEnv)> {
+        Some((self.types[*self.order.get(name)?], &self.env))
     }
 }
 
@@ -65,12 +145,14 @@ pub struct LFunctionBuilder<'a> {
 }
 
 impl<'a> LFunctionBuilderData<'a> {
-    pub fn new(builder: &'a mut SymbolSpaceBuilder) -> Self {
+    pub fn new(builder: &'a mut SymbolSpaceBuilder, env: GEnv) -> Self {
         Self {
+            env,
             instrs: Vec::new(),
             struct_insts: HashMap::new(),
             stack: HashMap::new(),
             subvar_map: HashMap::new(),
+            places: HashMap::new(),
             makes_call: false,
             builder,
             loopp: None,
@@ -83,7 +165,13 @@ pub struct LFunctionBuilderData<'a> {
     instrs: Vec<LInstruction>,
     stack: HashMap<VarID, Size>,
     subvar_map: HashMap<VarID, VarOffset>,
-    struct_insts: HashMap<StructInst, LStructInst>,
+    /// vars standing for the memory behind a reference, and that reference;
+    /// moves into them are written back through it
+    places: HashMap<VarID, VarID>,
+    /// keyed by the struct and its substituted generic args
+    struct_insts: HashMap<(StructID, Vec<TypeKey>), LStructInst>,
+    /// generic args of the instance being lowered
+    env: GEnv,
     makes_call: bool,
     loopp: Option<LoopCtx>,
 }
@@ -95,12 +183,22 @@ pub struct LoopCtx {
 }
 
 impl<'a> LFunctionBuilder<'a> {
-    pub fn new(program: &'a UProgram, builder: &'a mut SymbolSpaceBuilder) -> Self {
+    pub fn new(program: &'a UProgram, builder: &'a mut SymbolSpaceBuilder, env: GEnv) -> Self {
         Self {
-            data: LFunctionBuilderData::new(builder),
+            data: LFunctionBuilderData::new(builder, env),
             program,
         }
     }
+    pub fn func_inst(&mut self, f: &FnInst) -> Symbol {
+        let p = self.program;
+        let inst = FnInstance {
+            id: f.id,
+            env: GEnv::new(&p.fns[f.id].gargs, &f.gargs, &self.data.env),
+            key: gargs_key(p, &f.gargs, &self.data.env),
+            suffix: gargs_name(p, &f.gargs, &self.data.env),
+        };
+        self.data.builder.func(inst)
+    }
     pub fn alloc_stack(&mut self, i: VarID) -> Option<()> {
         if self
             .data
@@ -126,6 +224,47 @@ impl<'a> LFunctionBuilder<'a> {
         if off.id != i {
             self.subvar_map.insert(i, off);
         }
+        self.load_deref(off.id);
+    }
+    /// implicit derefs are loaded again on every use,
+    /// so they see writes made through other references
+    fn load_deref(&mut self, var: VarID) {
+        let v = &self.program.vars[var];
+        let Some(rf) = v.parent else {
+            return;
+        };
+        if v.name != DEREF_VAR {
+            return;
+        }
+        self.map_subvar(rf);
+        let size = self
+            .data
+            .size_of_var(self.program, var)
+            .expect("unsized type");
+        self.data.stack.entry(var).or_insert(size);
+        self.data.places.insert(var, rf);
+        self.instrs.push(LInstruction::Load {
+            dst: var,
+            src: rf,
+            size: size.div_ceil(8),
+        });
+    }
+    /// a move into (part of) a deref'd var writes through the reference it came from
+    fn write_back(&mut self, dst: VarID) {
+        let off = self.data.var_offset(self.program, dst).expect("var offset");
+        let Some(&rf) = self.data.places.get(&off.id) else {
+            return;
+        };
+        let size = self
+            .data
+            .size_of_var(self.program, dst)
+            .expect("unsized type");
+        self.instrs.push(LInstruction::Store {
+            dst: rf,
+            offset: off.offset / 8,
+            src: dst,
+            size: size.div_ceil(8),
+        });
     }
     pub fn insert_instr(&mut self, i: &UInstrInst) -> Option<Option<String>> {
         match i
@@ -136,12 +275,38 @@ impl<'a> LFunctionBuilder<'a> {
             UInstruction::Mv { dst, src } => {
                 self.alloc_stack(dst)?;
                 self.map_subvar(src);
+                let size = self
+                    .data
+                    .size_of_var(self.program, src)
+                    .expect("unsized type");
+                if let Some(im) = self.dyn_coercion(dst, src) {
+                    // concrete ref -> trait object: copy the data pointer, then add the vtable
+                    let vtable = self.vtable(im);
+                    self.instrs.push(LInstruction::Mv {
+                        dst,
+                        dst_offset: 0,
+                        src,
+                        src_offset: 0,
+                        size,
+                    });
+                    // the vtable goes right after the data pointer
+                    let offset = self.data.addr_size() / 8;
+                    self.instrs.push(LInstruction::LoadAddr {
+                        dst,
+                        offset,
+                        src: vtable,
+                    });
+                    self.write_back(dst);
+                    return Some(None);
+                }
                 self.instrs.push(LInstruction::Mv {
                     dst,
                     dst_offset: 0,
                     src,
                     src_offset: 0,
+                    size,
                 });
+                self.write_back(dst);
             }
             UInstruction::Ref { dst, src } => {
                 self.alloc_stack(dst)?;
@@ -149,7 +314,18 @@ impl<'a> LFunctionBuilder<'a> {
                 self.instrs.push(LInstruction::Ref { dst, src });
             }
             UInstruction::Deref { dst, src } => {
-                todo!()
+                self.alloc_stack(dst)?;
+                self.map_subvar(src);
+                let size = self
+                    .data
+                    .size_of_var(self.program, dst)
+                    .expect("unsized type");
+                self.instrs.push(LInstruction::Load {
+                    dst,
+                    src,
+                    size: size.div_ceil(8),
+                });
+                self.data.places.insert(dst, src);
             }
             UInstruction::LoadData { dst, src } => {
                 self.alloc_stack(dst)?;
@@ -182,24 +358,25 @@ impl<'a> LFunctionBuilder<'a> {
                 );
                 self.instrs.push(LInstruction::LoadAddr {
                     dst,
-                    offset: 0,
+                    offset: SLICE_PTR_OFFSET / 8,
                     src: sym,
                 });
 
-                let sym = self
-                    .builder
-                    .anon_ro_data(&(*len as u64).to_le_bytes(), Some(format!("len: {}", len)));
+                let addr = self.data.addr_size() / 8;
+                let sym = self.builder.anon_ro_data(
+                    &(*len as u64).to_le_bytes()[..addr as usize],
+                    Some(format!("len: {}", len)),
+                );
                 self.instrs.push(LInstruction::LoadData {
                     dst,
-                    offset: 8,
-                    len: 8,
+                    offset: SLICE_LEN_OFFSET / 8,
+                    len: addr,
                     src: sym,
                 });
             }
             UInstruction::Call { dst, f, args } => {
                 self.alloc_stack(dst);
                 self.makes_call = true;
-                let sym = self.builder.func(f.id);
                 let ret_size = self
                     .data
                     .size_of_var(self.program, dst)
@@ -209,21 +386,33 @@ impl<'a> LFunctionBuilder<'a> {
                 } else {
                     None
                 };
-                let call = LInstruction::Call {
-                    dst,
-                    f: sym,
-                    args: args
-                        .into_iter()
-                        .map(|id| {
-                            self.map_subvar(id);
-                            (
-                                id,
-                                self.data
-                                    .size_of_var(self.program, id)
-                                    .expect("unsized type"),
-                            )
-                        })
-                        .collect(),
+                let args = args
+                    .into_iter()
+                    .map(|id| {
+                        self.map_subvar(id);
+                        (
+                            id,
+                            self.data
+                                .size_of_var(self.program, id)
+                                .expect("unsized type"),
+                        )
+                    })
+                    .collect();
+                let call = match f {
+                    Callee::Fn(f) => LInstruction::Call {
+                        dst,
+                        f: self.func_inst(&f),
+                        args,
+                    },
+                    Callee::Dyn(m) => {
+                        self.map_subvar(m.recv);
+                        LInstruction::CallDyn {
+                            dst,
+                            obj: m.recv,
+                            idx: m.idx,
+                            args,
+                        }
+                    }
                 };
                 self.instrs.push(call);
             }
@@ -234,11 +423,11 @@ impl<'a> LFunctionBuilder<'a> {
                     match a.ty {
                         AsmBlockArgType::In => {
                             self.map_subvar(a.var);
-                            inputs.push((a.reg, a.var))
+                            inputs.push((a.reg, a.var, self.int_ty(a.var)))
                         }
                         AsmBlockArgType::Out => {
                             self.alloc_stack(a.var)?;
-                            outputs.push((a.reg, a.var));
+                            outputs.push((a.reg, a.var, self.int_ty(a.var)));
                         }
                     }
                 }
@@ -278,6 +467,10 @@ impl<'a> LFunctionBuilder<'a> {
                             .field_offset(self.program, struc, field)
                             .expect("field offset"),
                         src_offset: 0,
+                        size: self
+                            .data
+                            .size_of_var(self.program, src)
+                            .expect("unsized type"),
                     };
                     self.instrs.push(i)
                 }
@@ -285,7 +478,8 @@ impl<'a> LFunctionBuilder<'a> {
             UInstruction::If { cond, body } => {
                 self.map_subvar(cond);
                 let sym = self.builder.reserve();
-                self.instrs.push(LInstruction::Branch { to: *sym, cond });
+                let branch = self.fuse_cmp(*sym, cond);
+                self.instrs.push(branch);
                 for i in body {
                     self.insert_instr(&i);
                 }
@@ -307,6 +501,123 @@ impl<'a> LFunctionBuilder<'a> {
                 self.instrs.push(LInstruction::Mark(*bot));
                 self.loopp = old;
             }
+            UInstruction::Array { dst, elems } => {
+                self.alloc_stack(dst)?;
+                let mut offset = 0;
+                for src in elems {
+                    self.map_subvar(src);
+                    let size = self
+                        .data
+                        .size_of_var(self.program, src)
+                        .expect("unsized type");
+                    self.instrs.push(LInstruction::Mv {
+                        dst,
+                        dst_offset: offset,
+                        src,
+                        src_offset: 0,
+                        size,
+                    });
+                    offset += size;
+                }
+            }
+            UInstruction::Index { dst, src, idx, oob } => {
+                self.alloc_stack(dst)?;
+                self.map_subvar(src);
+                self.map_subvar(idx);
+                let (size, len) = self.elem_layout(src).expect("indexed a non array");
+                let check = self.bounds_check(oob);
+                let idx = (idx, self.int_ty(idx).expect("non number index"));
+                self.instrs.push(LInstruction::Index {
+                    dst,
+                    src,
+                    idx,
+                    size,
+                    len,
+                    check,
+                });
+            }
+            UInstruction::SliceRange {
+                dst,
+                src,
+                start,
+                end,
+                oob,
+            } => {
+                self.alloc_stack(dst)?;
+                self.map_subvar(src);
+                for i in start.iter().chain(&end) {
+                    self.map_subvar(*i);
+                }
+                let (size, len) = self.elem_layout(src).expect("sliced a non array");
+                let check = self.bounds_check(oob);
+                let start = start.map(|i| (i, self.int_ty(i).expect("non number index")));
+                let end = end.map(|i| (i, self.int_ty(i).expect("non number index")));
+                self.instrs.push(LInstruction::SliceRange {
+                    dst,
+                    src,
+                    start,
+                    end,
+                    size,
+                    len,
+                    check,
+                });
+            }
+            UInstruction::Len { dst, src } => {
+                self.map_subvar(src);
+                match self.elem_layout(src).expect("len of a non array") {
+                    (_, Some(len)) => self.load_const(dst, len as u64, "len"),
+                    (_, None) => {
+                        self.alloc_stack(dst)?;
+                        self.instrs.push(LInstruction::Mv {
+                            dst,
+                            dst_offset: 0,
+                            src,
+                            src_offset: SLICE_LEN_OFFSET,
+                            size: self.data.addr_size(),
+                        });
+                    }
+                }
+            }
+            UInstruction::SizeOf { dst, ty } => {
+                let size = self
+                    .data
+                    .size_of_type(self.program, &ty)
+                    .expect("unsized type");
+                self.load_const(dst, size.div_ceil(8) as u64, "size");
+            }
+            UInstruction::AlignOf { dst, ty } => {
+                let env = self.data.env.clone();
+                let align = self
+                    .data
+                    .align_in(self.program, ty, &env)
+                    .expect("unsized type");
+                self.load_const(dst, align.div_ceil(8) as u64, "align");
+            }
+            UInstruction::BinOp { dst, op, lhs, rhs } => {
+                self.alloc_stack(dst)?;
+                self.map_subvar(lhs);
+                self.map_subvar(rhs);
+                let int = self.int_ty(lhs).expect("operator on a non number");
+                self.instrs.push(LInstruction::BinOp {
+                    dst,
+                    op,
+                    lhs,
+                    rhs,
+                    int,
+                });
+            }
+            UInstruction::Cast { dst, src } => {
+                self.alloc_stack(dst)?;
+                self.map_subvar(src);
+                // references are cast as the address they hold
+                let addr = IntTy {
+                    signed: false,
+                    bits: self.data.addr_size(),
+                };
+                let from = self.int_ty(src).unwrap_or(addr);
+                let to = self.int_ty(dst).unwrap_or(addr);
+                self.instrs.push(LInstruction::Cast { dst, src, from, to });
+            }
             UInstruction::Break => {
                 self.data.instrs.push(LInstruction::Jump(
                     self.data.loopp.expect("Tried to break outside of loop").bot,
@@ -321,6 +632,127 @@ impl<'a> LFunctionBuilder<'a> {
         Some(None)
     }
 
+    /// element size in bytes, and the length if `var` is an array rather than a slice
+    fn elem_layout(&mut self, var: VarID) -> Option<(Len, Option<Len>)> {
+        let p = self.program;
+        let env = self.data.env.clone();
+        let (ty, env) = env.resolve(p, p.var_ty(var)?);
+        let (elem, len) = match p.types[ty] {
+            Type::Array(elem, len) => (elem, Some(len)),
+            Type::Slice(elem) => (elem, None),
+            _ => return None,
+        };
+        let env = env.clone();
+        let size = self.data.size_in(p, elem, &env)?;
+        Some((size.div_ceil(8), len))
+    }
+
+    /// a branch to `to` when `cond` is false; if `cond` is a temp that the comparison right
+    /// before just wrote, the comparison is folded into the branch instead
+    fn fuse_cmp(&mut self, to: Symbol, cond: VarID) -> LInstruction {
+        if let Some(&LInstruction::BinOp {
+            dst,
+            op,
+            lhs,
+            rhs,
+            int,
+        }) = self.instrs.last()
+            && dst == cond
+            && op.is_cmp()
+            && is_temp(&self.program.vars[cond].name)
+        {
+            self.instrs.pop();
+            return LInstruction::BranchCmp {
+                to,
+                op,
+                lhs,
+                rhs,
+                int,
+            };
+        }
+        LInstruction::Branch { to, cond }
+    }
+
+    /// the int type of `var`, if it's a number; raw bits act like a signed int,
+    /// same as the helpers in `std::ops`, bools like a `u8` and chars like a `u32`
+    fn int_ty(&mut self, var: VarID) -> Option<IntTy> {
+        let p = self.program;
+        let env = self.data.env.clone();
+        let (ty, _) = env.resolve(p, p.var_ty(var)?);
+        match p.types[ty] {
+            Type::Int(int) => Some(int),
+            Type::Bits(bits) => Some(IntTy { signed: true, bits }),
+            Type::Bool => Some(IntTy {
+                signed: false,
+                bits: 8,
+            }),
+            Type::Char => Some(IntTy {
+                signed: false,
+                bits: 32,
+            }),
+            _ => None,
+        }
+    }
+
+    fn bounds_check(&mut self, oob: DataID) -> BoundsCheck {
+        let data = &self.program.data[oob];
+        let msg = self
+            .data
+            .builder
+            .ro_data(oob, &data.content, Some(&data.name));
+        BoundsCheck {
+            msg,
+            msg_len: data.content.len() as Len,
+            fail: *self.data.builder.reserve(),
+            ok: *self.data.builder.reserve(),
+        }
+    }
+
+    /// `val` cut down to the size of `dst`, so a narrow field doesn't write over its neighbours
+    fn load_const(&mut self, dst: VarID, val: u64, label: &str) {
+        if self.alloc_stack(dst).is_none() {
+            return;
+        }
+        let len = self
+            .data
+            .size_of_var(self.program, dst)
+            .expect("unsized type")
+            .div_ceil(8)
+            .min(8);
+        let sym = self.builder.anon_ro_data(
+            &val.to_le_bytes()[..len as usize],
+            Some(format!("{label}: {val}")),
+        );
+        self.instrs.push(LInstruction::LoadData {
+            dst,
+            offset: 0,
+            len,
+            src: sym,
+        });
+    }
+
+    /// if this move turns a concrete reference into a trait object, returns the impl to use
+    fn dyn_coercion(&self, dst: VarID, src: VarID) -> Option<ImplID> {
+        let p = self.program;
+        let tr = p.dyn_trait(p.var_ty(dst)?)?;
+        let src_ty = p.var_ty(src)?;
+        if p.dyn_trait(src_ty).is_some() {
+            return None;
+        }
+        let &Type::Ref(inner) = &p.types[src_ty] else {
+            return None;
+        };
+        p.find_impl(tr, inner)
+    }
+
+    pub fn vtable(&mut self, im: ImplID) -> Symbol {
+        let p = self.program;
+        let imp = &p.impls[im];
+        let tr = imp.trait_.trai(p).expect("unresolved trait");
+        let label = format!("vtable {} for {}", p.traits[tr].name, p.type_name(imp.ty));
+        self.data.builder.vtable(im, &imp.methods, Some(label))
+    }
+
     pub fn finish(mut self, f: &UFunc) -> IRLFunction {
         IRLFunction {
             args: f
@@ -348,95 +780,123 @@ impl<'a> LFunctionBuilder<'a> {
 }
 
 impl LFunctionBuilderData<'_> {
-    pub fn var_offset(&mut self, p: &UProgram, mut var: VarID) -> Option<VarOffset> {
-        let mut path = Vec::new();
-        while let Type::Field(parent) = &p.get(var)?.ty {
-            var = parent.parent;
-            path.push(&parent.name);
-        }
-        let mut ty = &p.get(var)?.ty;
-        let mut offset = 0;
-        while let Type::Struct(sty) = ty {
-            let Some(name) = path.pop() else {
-                break;
-            };
-            offset += self.field_offset(p, sty, &name)?;
-            ty = p.struct_field_type(sty, name).expect("bad field");
-        }
-        Some(VarOffset { id: var, offset })
+    /// fields live inside their parent; anything else, including implicit derefs,
+    /// gets its own slot
+    pub fn var_offset(&mut self, p: &UProgram, var: VarID) -> Option<VarOffset> {
+        let v = &p.vars[var];
+        let own = VarOffset { id: var, offset: 0 };
+        let Some(parent) = v.parent else {
+            return Some(own);
+        };
+        let env = self.env.clone();
+        let (ty, _) = env.resolve(p, p.var_ty(parent)?);
+        let offset = match &p.types[ty] {
+            Type::Slice(_) => slice_field_offset(&v.name)?,
+            Type::Struct(sty) => self.field_offset(p, sty, &v.name)?,
+            _ => return Some(own),
+        };
+        let parent = self.var_offset(p, parent)?;
+        Some(VarOffset {
+            id: parent.id,
+            offset: parent.offset + offset,
+        })
     }
     pub fn addr_size(&self) -> Size {
         64
     }
-    pub fn struct_inst(&mut self, p: &UProgram, ty: &StructInst) -> &LStructInst {
+    pub fn struct_inst(&mut self, p: &UProgram, si: &StructInst, env: &GEnv) -> &LStructInst {
+        let key = (si.id, gargs_key(p, &si.gargs, env));
         // normally I'd let Some(..) here and return, but polonius does not exist :grief:
-        if self.struct_insts.get(ty).is_none() {
-            let LStructInst { id, args } = ty;
-            let struc = p.expect(*id);
-            let mut types = Vec::new();
-            let mut sizes = struc
-                .fields
-                .iter()
-                .map(|(n, f)| {
-                    let ty = if let Type::Generic { id } = &f.ty {
-                        struc
-                            .generics
-                            .iter()
-                            .enumerate()
-                            .find_map(|(i, g)| if *g == *id { args.get(i) } else { None })
-                            .unwrap_or(&f.ty)
-                    } else {
-                        &f.ty
-                    };
-                    types.push(ty.clone());
-                    (n, self.size_of_type(p, ty).expect("unsized type"))
-                })
-                .collect::<Vec<_>>();
-            sizes.sort_by(|(n1, s1, ..), (n2, s2, ..)| s1.cmp(s2).then_with(|| n1.cmp(n2)));
+        if self.struct_insts.get(&key).is_none() {
+            let struc = &p.structs[si.id];
+            let fenv = GEnv::new(&struc.gargs, &si.gargs, env);
+            // the same layout C uses: fields in declaration order, each padded out to its
+            // alignment, and the whole thing padded so the next one in an array is aligned too
             let mut offset = 0;
+            let mut align = 8;
             let mut offsets = Vec::new();
+            let mut types = Vec::new();
             let mut order = HashMap::new();
-            for (i, (name, size)) in sizes.iter().rev().enumerate() {
-                // TODO: alignment!!!
-                order.insert(name.to_string(), i);
+            for (i, (name, f)) in struc.fields.iter().enumerate() {
+                let size = self.size_in(p, f.ty, &fenv).expect("unsized type");
+                let falign = self.align_in(p, f.ty, &fenv).expect("unsized type");
+                offset = offset.next_multiple_of(falign);
+                align = align.max(falign);
+                order.insert(name.clone(), i);
                 offsets.push(offset);
+                types.push(f.ty);
                 offset += size;
             }
             self.struct_insts.insert(
-                ty.clone(),
+                key.clone(),
                 LStructInst {
                     offsets,
                     order,
                     types,
-                    size: offset,
+                    size: offset.next_multiple_of(align),
+                    align,
+                    env: fenv,
                 },
             );
         }
-        self.struct_insts.get(ty).unwrap()
+        self.struct_insts.get(&key).unwrap()
     }
 
     pub fn field_offset(&mut self, p: &UProgram, sty: &StructInst, field: &str) -> Option<Len> {
-        let inst = self.struct_inst(p, sty);
+        let env = self.env.clone();
+        let inst = self.struct_inst(p, sty, &env);
         Some(inst.offset(field)?)
     }
 
     pub fn size_of_type(&mut self, p: &UProgram, ty: &TypeID) -> Option<Size> {
+        let env = self.env.clone();
+        self.size_in(p, *ty, &env)
+    }
+
+    /// size of a type with its generics looked up in `env`
+    pub fn size_in(&mut self, p: &UProgram, ty: TypeID, env: &GEnv) -> Option<Size> {
+        let (ty, env) = env.resolve(p, ty);
         // TODO: target matters
         Some(match &p.types[ty] {
             Type::Bits(b) => *b,
-            Type::Struct(ty) => self.struct_inst(p, ty).size,
-            Type::Generic(id) => return None,
+            Type::Int(int) => int.bits,
+            Type::Bool => 8,
+            Type::Char => 32,
+            Type::Struct(si) => self.struct_inst(p, si, env).size,
+            // only reachable if a generic was never given a type
+            Type::Generic(_) => return None,
             // function references are resolved at compile time into direct calls,
             // so they don't have any size as arguments
-            Type::FnInst(fi) => 0,
+            Type::FnInst(_) => 0,
+            // references to trait objects carry a vtable pointer as well
+            &Type::Ref(t) if matches!(p.types[env.resolve(p, t).0], Type::Dyn(_)) => {
+                self.addr_size() * 2
+            }
             Type::Ref(_) => self.addr_size(),
-            Type::Array(ty, len) => self.size_of_type(p, ty)? * len,
+            &Type::Array(ty, len) => self.size_in(p, ty, env)? * len,
             Type::Slice(_) => self.addr_size() * 2,
             Type::Unit => 0,
             _ => return None,
         })
     }
 
+    /// alignment in bits; scalars are aligned to their size, up to the address size
+    pub fn align_in(&mut self, p: &UProgram, ty: TypeID, env: &GEnv) -> Option<Size> {
+        let (ty, env) = env.resolve(p, ty);
+        Some(match &p.types[ty] {
+            Type::Bits(b) => b.next_power_of_two().clamp(8, self.addr_size()),
+            Type::Int(int) => int.bits,
+            Type::Bool => 8,
+            Type::Char => 32,
+            Type::Struct(si) => self.struct_inst(p, si, env).align,
+            Type::Generic(_) => return None,
+            Type::Ref(_) | Type::Slice(_) => self.addr_size(),
+            &Type::Array(ty, _) => self.align_in(p, ty, env)?,
+            Type::FnInst(_) | Type::Unit => 8,
+            _ => return None,
+        })
+    }
+
     pub fn size_of_var(&mut self, p: &UProgram, var: VarID) -> Option<Size> {
         self.size_of_type(p, &p.get(var)?.ty)
     }
@@ -38,7 +38,7 @@ impl UInstruction {
             },
             I::Call { dst, f, args } => I::Call {
                 dst: dst.var(p)?,
-                f: f.fun(p)?.clone(),
+                f: f.callee(p)?,
                 args: args.iter().map(|i| i.var(p)).try_collect()?,
             },
             I::AsmBlock { instructions, args } => I::AsmBlock {
@@ -64,6 +64,51 @@ impl UInstruction {
             I::Loop { body } => I::Loop {
                 body: body.iter().map(|i| i.resolve(p)).try_collect()?,
             },
+            I::Array { dst, elems } => I::Array {
+                dst: dst.var(p)?,
+                elems: elems.iter().map(|e| e.var(p)).try_collect()?,
+            },
+            I::Index { dst, src, idx, oob } => I::Index {
+                dst: dst.var(p)?,
+                src: src.var(p)?,
+                idx: idx.var(p)?,
+                oob: *oob,
+            },
+            I::SliceRange {
+                dst,
+                src,
+                start,
+                end,
+                oob,
+            } => I::SliceRange {
+                dst: dst.var(p)?,
+                src: src.var(p)?,
+                start: start.as_ref().map(|s| s.var(p)).transpose()?,
+                end: end.as_ref().map(|e| e.var(p)).transpose()?,
+                oob: *oob,
+            },
+            I::Len { dst, src } => I::Len {
+                dst: dst.var(p)?,
+                src: src.var(p)?,
+            },
+            I::SizeOf { dst, ty } => I::SizeOf {
+                dst: dst.var(p)?,
+                ty: *ty,
+            },
+            I::AlignOf { dst, ty } => I::AlignOf {
+                dst: dst.var(p)?,
+                ty: *ty,
+            },
+            I::BinOp { dst, op, lhs, rhs } => I::BinOp {
+                dst: dst.var(p)?,
+                op: *op,
+                lhs: lhs.var(p)?,
+                rhs: rhs.var(p)?,
+            },
+            I::Cast { dst, src } => I::Cast {
+                dst: dst.var(p)?,
+                src: src.var(p)?,
+            },
             I::Break => I::Break,
             I::Continue => I::Continue,
         })
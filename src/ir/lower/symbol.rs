@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use super::{DataID, FnID, IRLFunction};
+use super::{DataID, FnID, FnInstance, IRLFunction, ImplID, Len, TypeKey};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub struct Symbol(usize);
@@ -15,8 +15,18 @@ impl std::ops::Deref for WritableSymbol {
     }
 }
 
+/// a slot in ro data that gets filled with the offset from the start of `data` to `target`
+/// once everything is placed; used for vtables
+#[derive(Debug, Clone, Copy)]
+pub struct DataReloc {
+    pub data: Symbol,
+    pub offset: Len,
+    pub target: Symbol,
+}
+
 pub struct SymbolSpace {
     ro_data: Vec<(Symbol, Vec<u8>)>,
+    relocs: Vec<DataReloc>,
     fns: Vec<(Symbol, IRLFunction)>,
     len: usize,
     labels: Vec<Option<String>>,
@@ -24,10 +34,12 @@ pub struct SymbolSpace {
 
 pub struct SymbolSpaceBuilder {
     symbols: usize,
-    unwritten_fns: Vec<(WritableSymbol, FnID)>,
-    fn_map: HashMap<FnID, Symbol>,
+    unwritten_fns: Vec<(WritableSymbol, FnInstance)>,
+    fn_map: HashMap<(FnID, Vec<TypeKey>), Symbol>,
     data_map: HashMap<DataID, Symbol>,
+    vtable_map: HashMap<ImplID, Symbol>,
     ro_data: Vec<(Symbol, Vec<u8>)>,
+    relocs: Vec<DataReloc>,
     fns: Vec<(Symbol, IRLFunction)>,
     labels: Vec<Option<String>>,
 }
@@ -36,6 +48,9 @@ impl SymbolSpace {
     pub fn ro_data(&self) -> &[(Symbol, Vec<u8>)] {
         &self.ro_data
     }
+    pub fn relocs(&self) -> &[DataReloc] {
+        &self.relocs
+    }
     pub fn fns(&self) -> &[(Symbol, IRLFunction)] {
         &self.fns
     }
@@ -54,7 +69,9 @@ impl SymbolSpaceBuilder {
             unwritten_fns: Vec::new(),
             fn_map: HashMap::new(),
             data_map: HashMap::new(),
+            vtable_map: HashMap::new(),
             ro_data: Vec::new(),
+            relocs: Vec::new(),
             fns: Vec::new(),
             labels: Vec::new(),
         }
@@ -62,11 +79,11 @@ impl SymbolSpaceBuilder {
     pub fn with_entries(entries: &[FnID]) -> SymbolSpaceBuilder {
         let mut s = Self::new();
         for e in entries {
-            s.func(*e);
+            s.func(FnInstance::plain(*e));
         }
         s
     }
-    pub fn pop_fn(&mut self) -> Option<(WritableSymbol, FnID)> {
+    pub fn pop_fn(&mut self) -> Option<(WritableSymbol, FnInstance)> {
         self.unwritten_fns.pop()
     }
     pub fn anon_ro_data(&mut self, data: &[u8], label: Option<String>) -> Symbol {
@@ -83,14 +100,33 @@ impl SymbolSpaceBuilder {
             }
         }
     }
-    pub fn func(&mut self, id: FnID) -> Symbol {
-        match self.fn_map.get(&id) {
+    /// one 8 byte entry per method, each holding the method's offset from the vtable
+    pub fn vtable(&mut self, id: ImplID, methods: &[FnID], label: Option<String>) -> Symbol {
+        if let Some(s) = self.vtable_map.get(&id) {
+            return *s;
+        }
+        let sym = self.reserve();
+        self.vtable_map.insert(id, *sym);
+        for (i, &f) in methods.iter().enumerate() {
+            let target = self.func(FnInstance::plain(f));
+            self.relocs.push(DataReloc {
+                data: *sym,
+                offset: i as Len * 8,
+                target,
+            });
+        }
+        self.write_ro_data(sym, vec![0; methods.len() * 8], label)
+    }
+    /// one symbol per instance; generic fns are keyed by their substituted args
+    pub fn func(&mut self, inst: FnInstance) -> Symbol {
+        let key = (inst.id, inst.key.clone());
+        match self.fn_map.get(&key) {
             Some(s) => *s,
             None => {
                 let wsym = self.reserve();
                 let sym = *wsym;
-                self.unwritten_fns.push((wsym, id));
-                self.fn_map.insert(id, sym);
+                self.unwritten_fns.push((wsym, inst));
+                self.fn_map.insert(key, sym);
                 sym
             }
         }
@@ -115,6 +151,11 @@ impl SymbolSpaceBuilder {
         self.labels[sym.0 .0] = name;
         *sym
     }
+    /// for symbols the target fills in itself, like the startup shim
+    pub fn write_fn_label(&mut self, sym: WritableSymbol, name: String) -> Symbol {
+        self.labels[sym.0 .0] = Some(name);
+        *sym
+    }
     pub fn reserve(&mut self) -> WritableSymbol {
         let val = self.symbols;
         self.symbols += 1;
@@ -130,6 +171,7 @@ impl SymbolSpaceBuilder {
                 len: self.symbols,
                 fns: self.fns,
                 ro_data: self.ro_data,
+                relocs: self.relocs,
                 labels: self.labels,
             })
         } else {
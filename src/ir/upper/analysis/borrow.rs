@@ -0,0 +1,176 @@
+//! lexical borrow checking
+//!
+//! a reference taken with `@` (or by indexing/slicing an array) is a loan on the var that owns
+//! the memory; it's held by every var the reference gets moved into. a holder counts as live
+//! until its last read, and a read inside a loop that the holder was declared outside of
+//! keeps it live until the end of that loop. this is flow-insensitive, so it can reject
+//! some programs that are fine.
+
+use std::collections::{HashMap, HashSet};
+
+use super::*;
+
+struct Loan {
+    /// owner of the borrowed memory
+    var: VarID,
+    origin: Origin,
+    step: usize,
+}
+
+pub fn check_borrows(p: &UProgram, f: &UFunc, errs: &mut Vec<ResErr>) {
+    let flat = FlatBody::new(p, &f.instructions);
+    // block each var lives in, and every step that reads it
+    let mut scopes: HashMap<VarID, usize> = f.args.iter().map(|&a| (a, 0)).collect();
+    let mut reads: HashMap<VarID, Vec<usize>> = HashMap::new();
+    for (i, step) in flat.steps.iter().enumerate() {
+        let acc = step.inst.i.access(p);
+        for &v in acc.reads.iter().chain(&acc.writes) {
+            let scope = scopes.entry(v).or_insert(step.block);
+            *scope = flat.common(*scope, step.block);
+        }
+        for &v in &acc.reads {
+            let mut cur = Some(v);
+            while let Some(v) = cur {
+                reads.entry(v).or_default().push(i);
+                cur = p.vars[v].parent;
+            }
+        }
+    }
+    // last step a var is live at
+    let live_until = |v: VarID| {
+        let Some(rs) = reads.get(&v) else {
+            return None;
+        };
+        let decl = scopes.get(&v).copied().unwrap_or(0);
+        rs.iter()
+            .map(|&i| {
+                let mut end = i;
+                let mut b = Some(flat.steps[i].block);
+                while let Some(id) = b {
+                    let block = &flat.blocks[id];
+                    if block.looped && !flat.within(decl, id) {
+                        end = end.max(block.end);
+                    }
+                    b = block.parent;
+                }
+                end
+            })
+            .max()
+    };
+
+    let owner = |v: VarID| flat.owner(p, v);
+    let holder = |v: VarID| owner(v).unwrap_or(v);
+
+    let mut loans = Vec::new();
+    let mut held: HashMap<VarID, HashSet<usize>> = HashMap::new();
+    let mut reported = HashSet::new();
+    for (i, step) in flat.steps.iter().enumerate() {
+        let origin = step.inst.origin;
+        let mut lend = |dst: &IdentID, src: &IdentID, loans: &mut Vec<Loan>| {
+            let (Some(dst), Some(src)) = (dst.var(p), src.var(p)) else {
+                return;
+            };
+            if let Some(var) = owner(src) {
+                held.entry(holder(dst)).or_default().insert(loans.len());
+                loans.push(Loan {
+                    var,
+                    origin,
+                    step: i,
+                });
+            }
+        };
+        match &step.inst.i {
+            UInstruction::Ref { dst, src } => lend(dst, src, &mut loans),
+            UInstruction::Index { dst, src, .. } | UInstruction::SliceRange { dst, src, .. }
+                if src
+                    .var(p)
+                    .and_then(|v| p.var_ty(v))
+                    .is_some_and(|t| matches!(p.types[t], Type::Array(..))) =>
+            {
+                lend(dst, src, &mut loans)
+            }
+            // these read through the reference, but what they produce isn't one
+            UInstruction::Deref { .. } | UInstruction::Len { .. } => (),
+            UInstruction::Ret { src } => {
+                let Some(src) = src.var(p) else {
+                    continue;
+                };
+                for &l in held.get(&holder(src)).into_iter().flatten() {
+                    let loan = &loans[l];
+                    errs.push(ResErr::ReturnLocalRef {
+                        borrow: loan.origin,
+                        origin,
+                        var: loan.var,
+                    });
+                }
+                continue;
+            }
+            _ => {
+                // whatever was read passes its loans on to what's written
+                let acc = step.inst.i.access(p);
+                let from: HashSet<_> = acc
+                    .reads
+                    .iter()
+                    .filter_map(|&r| held.get(&holder(r)))
+                    .flatten()
+                    .copied()
+                    .collect();
+                for &w in acc.writes.iter().filter(|&&w| can_hold_ref(p, w)) {
+                    held.entry(holder(w)).or_default().extend(&from);
+                }
+            }
+        }
+        // writing to borrowed memory while a holder is still live
+        for &w in &step.inst.i.access(p).writes {
+            let Some(var) = owner(w) else {
+                continue;
+            };
+            for (l, loan) in loans.iter().enumerate() {
+                if loan.var != var || loan.step >= i || reported.contains(&l) {
+                    continue;
+                }
+                let live = held
+                    .iter()
+                    .filter(|(_, ls)| ls.contains(&l))
+                    .any(|(&h, _)| live_until(h).is_some_and(|end| end > i));
+                if live {
+                    reported.insert(l);
+                    errs.push(ResErr::BorrowedWrite {
+                        borrow: loan.origin,
+                        origin,
+                        var,
+                    });
+                }
+            }
+        }
+    }
+
+    // holders read after the borrowed var went out of scope
+    for (l, loan) in loans.iter().enumerate() {
+        let scope = scopes.get(&loan.var).copied().unwrap_or(0);
+        if scope == 0 {
+            continue;
+        }
+        let end = flat.blocks[scope].end;
+        let late = held
+            .iter()
+            .filter(|(_, ls)| ls.contains(&l))
+            .filter_map(|(h, _)| reads.get(h)?.iter().find(|&&r| r >= end))
+            .min();
+        if let Some(&r) = late {
+            errs.push(ResErr::BorrowOutlives {
+                borrow: loan.origin,
+                origin: flat.steps[r].inst.origin,
+                var: loan.var,
+            });
+        }
+    }
+}
+
+/// numbers can't carry a reference out of a call or asm block
+fn can_hold_ref(p: &UProgram, v: VarID) -> bool {
+    !matches!(
+        p.var_ty(v).map(|t| &p.types[t]),
+        Some(Type::Bits(_) | Type::Int(_) | Type::Bool | Type::Char | Type::Unit)
+    )
+}
@@ -0,0 +1,70 @@
+//! which parts of a function body control can reach
+//!
+//! an `if` might not be taken, and a `loop` only ends if something breaks out of it.
+
+use super::*;
+
+pub struct Flow {
+    /// control can reach the end of the body without returning
+    pub falls_through: bool,
+    /// first instruction of each run of code that can't be reached
+    pub unreachable: Vec<Origin>,
+}
+
+pub fn check_flow(p: &UProgram, f: &UFunc) -> Flow {
+    let mut ctx = FlowCtx {
+        p,
+        breaks: Vec::new(),
+        unreachable: Vec::new(),
+    };
+    let falls_through = ctx.block(&f.instructions);
+    Flow {
+        falls_through,
+        unreachable: ctx.unreachable,
+    }
+}
+
+struct FlowCtx<'a> {
+    p: &'a UProgram,
+    /// whether each enclosing loop has a reachable `break`
+    breaks: Vec<bool>,
+    unreachable: Vec<Origin>,
+}
+
+impl FlowCtx<'_> {
+    /// whether control can reach the end of `body`
+    fn block(&mut self, body: &[InstrID]) -> bool {
+        for (n, &id) in body.iter().enumerate() {
+            if !self.instr(id) {
+                if let Some(&next) = body.get(n + 1) {
+                    self.unreachable.push(self.p.instrs[next].origin);
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    /// whether control can continue past `id`
+    fn instr(&mut self, id: InstrID) -> bool {
+        match &self.p.instrs[id].i {
+            UInstruction::If { body, .. } => {
+                self.block(body);
+                true
+            }
+            UInstruction::Loop { body } => {
+                self.breaks.push(false);
+                self.block(body);
+                self.breaks.pop().expect("pushed above")
+            }
+            UInstruction::Break => {
+                if let Some(b) = self.breaks.last_mut() {
+                    *b = true;
+                }
+                false
+            }
+            UInstruction::Continue | UInstruction::Ret { .. } => false,
+            _ => true,
+        }
+    }
+}
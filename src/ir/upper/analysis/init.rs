@@ -0,0 +1,298 @@
+//! definite initialization and use after move
+//!
+//! walks the instruction tree keeping the set of vars that are initialized on every path so
+//! far and the ones that might have been moved out of. values behind a reference are never
+//! tracked; only the reference itself is.
+
+use std::collections::{HashMap, HashSet};
+
+use super::*;
+
+#[derive(Clone, PartialEq)]
+struct State {
+    init: HashSet<VarID>,
+    moved: HashMap<VarID, Origin>,
+    /// false once control can't reach here (after `return`, `break`, or `continue`)
+    live: bool,
+}
+
+impl State {
+    fn dead() -> Self {
+        Self {
+            init: HashSet::new(),
+            moved: HashMap::new(),
+            live: false,
+        }
+    }
+
+    /// state where control could have come from either `self` or `other`
+    fn join(self, other: Self) -> Self {
+        if !self.live {
+            return other;
+        }
+        if !other.live {
+            return self;
+        }
+        let mut moved = self.moved;
+        for (v, o) in other.moved {
+            moved.entry(v).or_insert(o);
+        }
+        Self {
+            init: self.init.intersection(&other.init).copied().collect(),
+            moved,
+            live: true,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LoopExits {
+    breaks: Option<State>,
+    continues: Option<State>,
+}
+
+fn join_into(slot: &mut Option<State>, st: State) {
+    *slot = Some(match slot.take() {
+        Some(s) => s.join(st),
+        None => st,
+    });
+}
+
+struct InitCtx<'a> {
+    p: &'a UProgram,
+    flat: FlatBody<'a>,
+    loops: Vec<LoopExits>,
+    errs: &'a mut Vec<ResErr>,
+    /// loop bodies are walked until nothing changes, so the same error can come up again
+    reported: HashSet<(InstrID, VarID)>,
+}
+
+pub fn check_init(p: &UProgram, f: &UFunc, errs: &mut Vec<ResErr>) {
+    let mut ctx = InitCtx {
+        p,
+        flat: FlatBody::new(p, &f.instructions),
+        loops: Vec::new(),
+        errs,
+        reported: HashSet::new(),
+    };
+    let st = State {
+        init: f.args.iter().copied().collect(),
+        moved: HashMap::new(),
+        live: true,
+    };
+    ctx.block(&f.instructions, st);
+}
+
+impl InitCtx<'_> {
+    fn block(&mut self, body: &[InstrID], mut st: State) -> State {
+        for &id in body {
+            st = self.instr(id, st);
+        }
+        st
+    }
+
+    fn instr(&mut self, id: InstrID, mut st: State) -> State {
+        let p = self.p;
+        let inst = &p.instrs[id];
+        if !st.live {
+            return st;
+        }
+        let origin = inst.origin;
+        let read = |s: &mut Self, st: &State, i: &IdentID| {
+            if let Some(v) = i.var(p) {
+                s.read(id, origin, st, v);
+            }
+        };
+        let mut moves = Vec::new();
+        match &inst.i {
+            UInstruction::If { cond, body } => {
+                read(self, &st, cond);
+                let after = self.block(body, st.clone());
+                return st.join(after);
+            }
+            UInstruction::Loop { body } => {
+                let mut head = st.clone();
+                loop {
+                    self.loops.push(LoopExits::default());
+                    let end = self.block(body, head.clone());
+                    let exits = self.loops.pop().expect("pushed above");
+                    let mut back = end;
+                    if let Some(c) = exits.continues {
+                        back = back.join(c);
+                    }
+                    let next = st.clone().join(back);
+                    if next == head {
+                        return exits.breaks.unwrap_or_else(State::dead);
+                    }
+                    head = next;
+                }
+            }
+            UInstruction::Break => {
+                let exits = self.loops.last_mut().expect("break outside of a loop");
+                join_into(&mut exits.breaks, st);
+                return State::dead();
+            }
+            UInstruction::Continue => {
+                let exits = self.loops.last_mut().expect("continue outside of a loop");
+                join_into(&mut exits.continues, st);
+                return State::dead();
+            }
+            UInstruction::Ret { src } => {
+                read(self, &st, src);
+                return State::dead();
+            }
+            // a reference to a var needs the var to be there, even though nothing is read yet
+            UInstruction::Ref { src, .. } => read(self, &st, src),
+            UInstruction::Mv { src, .. } => moves.push(src),
+            UInstruction::Call { args, .. } => moves.extend(args),
+            UInstruction::Construct { fields, .. } => moves.extend(fields.values()),
+            UInstruction::Array { elems, .. } => moves.extend(elems),
+            _ => (),
+        }
+        let acc = inst.i.access(p);
+        for &v in &acc.reads {
+            self.read(id, origin, &st, v);
+        }
+        for v in moves.into_iter().filter_map(|i| i.var(p)) {
+            if self.flat.owner(p, v).is_some() && !p.var_ty(v).is_none_or(|t| p.is_copy(t)) {
+                st.moved.insert(v, origin);
+            }
+        }
+        for &v in &acc.writes {
+            self.write(id, origin, &mut st, v);
+        }
+        st
+    }
+
+    fn read(&mut self, id: InstrID, origin: Origin, st: &State, v: VarID) {
+        let p = self.p;
+        let mut cur = v;
+        loop {
+            if self.flat.places.contains(&cur) {
+                break;
+            }
+            if p.vars[cur].name == DEREF_VAR {
+                let Some(parent) = p.vars[cur].parent else {
+                    break;
+                };
+                return self.read(id, origin, st, parent);
+            }
+            if let Some(&moved) = st.moved.get(&cur) {
+                return self.report(
+                    id,
+                    cur,
+                    ResErr::UseAfterMove {
+                        moved,
+                        origin,
+                        var: cur,
+                    },
+                );
+            }
+            match p.vars[cur].parent {
+                Some(parent) => cur = parent,
+                None => break,
+            }
+        }
+        // fields that were moved out of it
+        if let Some((&m, &moved)) = st.moved.iter().find(|&(&m, _)| self.contains(v, m)) {
+            return self.report(
+                id,
+                m,
+                ResErr::UseAfterMove {
+                    moved,
+                    origin,
+                    var: m,
+                },
+            );
+        }
+        if self.flat.owner(p, v).is_some() && !self.init(st, v) {
+            self.report(id, v, ResErr::Uninit { origin, var: v });
+        }
+    }
+
+    fn write(&mut self, id: InstrID, origin: Origin, st: &mut State, v: VarID) {
+        let p = self.p;
+        if self.flat.owner(p, v).is_none() {
+            // writes through a reference need the reference
+            let mut cur = v;
+            while let Some(parent) = p.vars[cur].parent {
+                if p.vars[cur].name == DEREF_VAR {
+                    return self.read(id, origin, st, parent);
+                }
+                cur = parent;
+            }
+            return;
+        }
+        st.moved.retain(|&m, _| !self.contains(v, m));
+        st.init.insert(v);
+    }
+
+    /// whether `v` or something it's a field of has been set
+    fn init(&self, st: &State, mut v: VarID) -> bool {
+        loop {
+            if st.init.contains(&v) {
+                return true;
+            }
+            match self.p.vars[v].parent {
+                Some(parent) => v = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// whether `inner` is `outer` or one of its fields
+    fn contains(&self, outer: VarID, mut inner: VarID) -> bool {
+        loop {
+            if inner == outer {
+                return true;
+            }
+            match self.p.vars[inner].parent {
+                Some(parent) => inner = parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn report(&mut self, id: InstrID, v: VarID, err: ResErr) {
+        if self.reported.insert((id, v)) {
+            self.errs.push(err);
+        }
+    }
+}
+
+/// structs marked copy can only have fields that are copy themselves
+pub fn check_copy_structs(p: &UProgram, errs: &mut Vec<ResErr>) {
+    for (id, struc) in p.structs.iter().enumerate() {
+        if !struc.copy {
+            continue;
+        }
+        for (name, field) in &struc.fields {
+            if !clean_type(&p.types, field.ty).is_none_or(|t| p.is_copy(t)) {
+                errs.push(ResErr::CopyField {
+                    origin: field.origin,
+                    id: id.into(),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// `#[repr(C)]` structs can only have fields that C could have too
+pub fn check_repr_structs(p: &UProgram, errs: &mut Vec<ResErr>) {
+    for (id, struc) in p.structs.iter().enumerate() {
+        if !struc.attrs.contains(&Attr::ReprC) {
+            continue;
+        }
+        for (name, field) in &struc.fields {
+            if !p.is_c_type(field.ty) {
+                errs.push(ResErr::ReprField {
+                    origin: field.origin,
+                    id: id.into(),
+                    name: name.clone(),
+                    ty: field.ty,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! checks that run over function bodies once everything is resolved
+
+mod borrow;
+mod flow;
+mod init;
+mod shadow;
+mod unused;
+
+use std::collections::HashSet;
+
+use super::*;
+
+pub use borrow::*;
+pub use flow::*;
+pub use init::*;
+pub use shadow::*;
+pub use unused::*;
+
+/// the vars an instruction touches; nested bodies aren't included
+#[derive(Default)]
+pub struct Access {
+    pub reads: Vec<VarID>,
+    pub writes: Vec<VarID>,
+}
+
+impl UInstruction {
+    pub fn access(&self, p: &UProgram) -> Access {
+        use UInstruction as I;
+        let vars = |ids: &[&IdentID]| ids.iter().filter_map(|i| i.var(p)).collect::<Vec<_>>();
+        let (reads, writes) = match self {
+            I::Mv { dst, src }
+            | I::Deref { dst, src }
+            | I::Len { dst, src }
+            | I::Cast { dst, src } => (vars(&[src]), vars(&[dst])),
+            // taking a reference reads nothing yet; see `borrow`
+            I::Ref { dst, .. } => (Vec::new(), vars(&[dst])),
+            I::LoadData { dst, .. }
+            | I::LoadSlice { dst, .. }
+            | I::SizeOf { dst, .. }
+            | I::AlignOf { dst, .. } => (Vec::new(), vars(&[dst])),
+            I::Call { dst, args, .. } => (vars(&args.iter().collect::<Vec<_>>()), vars(&[dst])),
+            I::AsmBlock { args, .. } => {
+                let mut acc = Access::default();
+                for a in args {
+                    let Some(v) = a.var.var(p) else {
+                        continue;
+                    };
+                    match a.ty {
+                        AsmBlockArgType::In => acc.reads.push(v),
+                        AsmBlockArgType::Out => acc.writes.push(v),
+                    }
+                }
+                return acc;
+            }
+            I::Ret { src } => (vars(&[src]), Vec::new()),
+            I::Construct { dst, fields, .. } => {
+                (vars(&fields.values().collect::<Vec<_>>()), vars(&[dst]))
+            }
+            I::If { cond, .. } => (vars(&[cond]), Vec::new()),
+            I::Loop { .. } | I::Break | I::Continue => (Vec::new(), Vec::new()),
+            I::Array { dst, elems } => (vars(&elems.iter().collect::<Vec<_>>()), vars(&[dst])),
+            I::Index { dst, src, idx, .. } => (vars(&[src, idx]), vars(&[dst])),
+            I::BinOp { dst, lhs, rhs, .. } => (vars(&[lhs, rhs]), vars(&[dst])),
+            I::SliceRange {
+                dst,
+                src,
+                start,
+                end,
+                ..
+            } => {
+                let mut reads = vec![src];
+                reads.extend(start.iter().chain(end));
+                (vars(&reads), vars(&[dst]))
+            }
+        };
+        Access { reads, writes }
+    }
+}
+
+/// a function body with its nested blocks laid out in order
+pub struct FlatBody<'a> {
+    pub steps: Vec<Step<'a>>,
+    /// the function body itself is block 0
+    pub blocks: Vec<Block>,
+    /// vars made by `Deref`, which name memory behind a reference
+    pub places: HashSet<VarID>,
+}
+
+pub struct Step<'a> {
+    pub inst: &'a UInstrInst,
+    pub block: usize,
+}
+
+/// the steps in `start..end`; nested blocks are included
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub parent: Option<usize>,
+    pub looped: bool,
+}
+
+impl<'a> FlatBody<'a> {
+    pub fn new(p: &'a UProgram, body: &[InstrID]) -> Self {
+        let mut flat = Self {
+            steps: Vec::new(),
+            blocks: Vec::new(),
+            places: HashSet::new(),
+        };
+        flat.push_block(p, body, None, false);
+        flat
+    }
+
+    fn push_block(
+        &mut self,
+        p: &'a UProgram,
+        body: &[InstrID],
+        parent: Option<usize>,
+        looped: bool,
+    ) {
+        let id = self.blocks.len();
+        self.blocks.push(Block {
+            start: self.steps.len(),
+            end: 0,
+            parent,
+            looped,
+        });
+        for &i in body {
+            let inst = &p.instrs[i];
+            self.steps.push(Step { inst, block: id });
+            match &inst.i {
+                UInstruction::Deref { dst, .. } => self.places.extend(dst.var(p)),
+                UInstruction::If { body, .. } => self.push_block(p, body, Some(id), false),
+                UInstruction::Loop { body } => self.push_block(p, body, Some(id), true),
+                _ => (),
+            }
+        }
+        self.blocks[id].end = self.steps.len();
+    }
+
+    /// whether block `inner` is `outer` or nested inside it
+    pub fn within(&self, mut inner: usize, outer: usize) -> bool {
+        loop {
+            if inner == outer {
+                return true;
+            }
+            match self.blocks[inner].parent {
+                Some(p) => inner = p,
+                None => return false,
+            }
+        }
+    }
+
+    /// the local var whose memory `v` is part of, or `None` if it's behind a reference
+    pub fn owner(&self, p: &UProgram, mut v: VarID) -> Option<VarID> {
+        loop {
+            if self.places.contains(&v) || p.vars[v].name == DEREF_VAR {
+                return None;
+            }
+            match p.vars[v].parent {
+                Some(parent) => v = parent,
+                None => return Some(v),
+            }
+        }
+    }
+
+    /// named vars in the order they're first set, with the block that happens in;
+    /// arguments come first
+    pub fn decls(&self, p: &UProgram, args: &[VarID]) -> Vec<(VarID, usize)> {
+        let mut seen = HashSet::new();
+        let args = args.iter().map(|&a| (a, 0));
+        let writes = self.steps.iter().flat_map(|s| {
+            let block = s.block;
+            s.inst
+                .i
+                .access(p)
+                .writes
+                .into_iter()
+                .map(move |w| (w, block))
+        });
+        args.chain(writes)
+            .filter(|&(v, _)| {
+                let var = &p.vars[v];
+                var.parent.is_none() && !is_temp(&var.name) && seen.insert(v)
+            })
+            .collect()
+    }
+
+    /// innermost block containing both `a` and `b`
+    pub fn common(&self, a: usize, b: usize) -> usize {
+        let mut a = a;
+        while !self.within(b, a) {
+            a = self.blocks[a].parent.expect("blocks share the root");
+        }
+        a
+    }
+}
+
+impl UProgram {
+    /// lints about code style only look at the package being built, not its dependencies
+    pub fn in_entry_package(&self, m: ModID) -> bool {
+        let root = |mut m: ModID| {
+            while let Some(parent) = self.modules[m].parent {
+                m = parent;
+            }
+            m
+        };
+        self.entry.is_some_and(|e| root(e) == root(m))
+    }
+}
+
+/// see `ModuleLowerCtx::temp_var`
+pub fn is_temp(name: &str) -> bool {
+    name.strip_prefix("temp")
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
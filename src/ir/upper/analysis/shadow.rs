@@ -0,0 +1,24 @@
+//! `let`s that reuse the name of a var that's still in scope
+
+use std::collections::HashMap;
+
+use super::*;
+
+pub fn check_shadowing(p: &UProgram, fid: FnID, f: &UFunc, warns: &mut Vec<ResWarn>) {
+    let flat = FlatBody::new(p, &f.instructions);
+    let mut names: HashMap<&str, Vec<(VarID, usize)>> = HashMap::new();
+    for (v, block) in flat.decls(p, &f.args) {
+        let var = &p.vars[v];
+        let prev = names.entry(&var.name).or_default();
+        // the var is in scope if its block is around this one
+        if let Some(&(old, _)) = prev.iter().rev().find(|&&(_, b)| flat.within(block, b)) {
+            warns.push(ResWarn::Shadowing {
+                fid,
+                origin: var.origin,
+                prev: p.vars[old].origin,
+                var: v,
+            });
+        }
+        prev.push((v, block));
+    }
+}
@@ -0,0 +1,159 @@
+//! unused variables, functions, imports, and struct fields
+//!
+//! only code in the same package as the entry module is checked, and anything named with a
+//! leading `_` is left alone. public items might be used from outside, so they're skipped.
+
+use std::collections::HashSet;
+
+use super::*;
+
+pub fn check_unused(p: &UProgram, warns: &mut Vec<ResWarn>) {
+    let Some(entry) = p.entry else {
+        return;
+    };
+    let local = |m: ModID| p.in_entry_package(m);
+    let opted_out = |name: &str| name.starts_with('_');
+
+    // a var counts as read if any field of it is
+    let mut read = HashSet::new();
+    for inst in &p.instrs {
+        let mut reads = inst.i.access(p).reads;
+        if let UInstruction::Ref { src, .. } = &inst.i {
+            reads.extend(src.var(p));
+        }
+        for v in reads {
+            let mut cur = Some(v);
+            while let Some(v) = cur
+                && read.insert(v)
+            {
+                cur = p.vars[v].parent;
+            }
+        }
+    }
+
+    for (fid, f) in p.fns.iter().enumerate() {
+        if !local(f.module) {
+            continue;
+        }
+        let flat = FlatBody::new(p, &f.instructions);
+        for (v, _) in flat.decls(p, &f.args) {
+            let var = &p.vars[v];
+            if !opted_out(&var.name) && !read.contains(&v) {
+                warns.push(ResWarn::UnusedVar {
+                    fid: fid.into(),
+                    origin: var.origin,
+                    var: v,
+                });
+            }
+        }
+    }
+
+    let mut fields_read = HashSet::new();
+    for &v in &read {
+        if let Some(parent) = p.vars[v].parent
+            && let Some(ty) = p.var_ty(parent)
+            && let Type::Struct(si) = &p.types[ty]
+        {
+            fields_read.insert((si.id, p.vars[v].name.as_str()));
+        }
+    }
+    for (id, struc) in p.structs.iter().enumerate() {
+        if !local(struc.module) {
+            continue;
+        }
+        let id = StructID::from(id);
+        for (name, field) in &struc.fields {
+            if !opted_out(name) && !fields_read.contains(&(id, name.as_str())) {
+                warns.push(ResWarn::UnusedField {
+                    origin: field.origin,
+                    id,
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+
+    // fns only count as used if they can be reached from something that isn't checked here,
+    // so dead fns that only call each other (or themselves) are still caught
+    let mut fns = Vec::new();
+    for (mid, module) in p.modules.iter().enumerate() {
+        let mid = ModID::from(mid);
+        if !local(mid) {
+            continue;
+        }
+        let mut members: Vec<_> = module.members.iter().collect();
+        members.sort_by_key(|(name, _)| *name);
+        for (name, mem) in members {
+            if mem.vis != Visibility::Private || opted_out(name) {
+                continue;
+            }
+            match &mem.id {
+                &MemberID::Fn(fid) => {
+                    let is_main = mid == entry && name == "main";
+                    // called by the runtime or the test runner
+                    let called = p.fns[fid]
+                        .attrs
+                        .iter()
+                        .any(|a| matches!(a, Attr::Entry | Attr::Test));
+                    if !is_main && !called {
+                        fns.push(fid);
+                    }
+                }
+                MemberID::Use(u) => {
+                    if !p.used_imports.contains(&(mid, name.clone())) {
+                        warns.push(ResWarn::UnusedImport {
+                            module: mid,
+                            origin: u.path[0].origin,
+                            name: name.clone(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    let reachable = reachable_fns(p, &fns);
+    for fid in fns {
+        if !reachable.contains(&fid) {
+            warns.push(ResWarn::UnusedFn { fid });
+        }
+    }
+}
+
+/// fns that can be called starting from any fn not in `checked`. fns used as values are
+/// counted as reachable too, since where they end up being called isn't tracked
+fn reachable_fns(p: &UProgram, checked: &[FnID]) -> HashSet<FnID> {
+    let calls: HashSet<_> = p
+        .instrs
+        .iter()
+        .filter_map(|inst| match &inst.i {
+            UInstruction::Call { f, .. } => Some(*f),
+            _ => None,
+        })
+        .collect();
+    let mut todo: Vec<_> = (0..p.fns.len())
+        .map(FnID::from)
+        .filter(|fid| !checked.contains(fid))
+        .collect();
+    for (i, ident) in p.idents.iter().enumerate() {
+        if let IdentStatus::Res(Res::Fn(fi)) = &ident.status
+            && !calls.contains(&IdentID::from(i))
+        {
+            todo.push(fi.id);
+        }
+    }
+    let mut seen = HashSet::new();
+    while let Some(fid) = todo.pop() {
+        if !seen.insert(fid) {
+            continue;
+        }
+        for step in FlatBody::new(p, &p.fns[fid].instructions).steps {
+            if let UInstruction::Call { f, .. } = &step.inst.i
+                && let Some(fi) = f.fun(p)
+            {
+                todo.push(fi.id);
+            }
+        }
+    }
+    seen
+}
@@ -19,11 +19,14 @@ pub enum IdentStatus {
     Unres {
         base: ResBase,
         path: Vec<MemberIdent>,
+        /// module the ident was written in; decides what private members it can see
+        scope: ModID,
     },
     Failed(Option<ResErr>),
     Cooked,
 }
 
+#[derive(Clone)]
 pub struct MemberIdent {
     pub ty: MemberTy,
     pub name: String,
@@ -63,6 +66,16 @@ pub enum Res {
     Type(TypeID),
     Generic(GenericID),
     Module(ModID),
+    Trait(TraitID),
+    Method(DynMethod),
+}
+
+/// a trait method looked up on a trait object; called through its vtable
+#[derive(Debug, Clone)]
+pub struct DynMethod {
+    pub recv: VarID,
+    pub tr: TraitID,
+    pub idx: usize,
 }
 
 impl Res {
@@ -74,6 +87,8 @@ impl Res {
             Res::Type(..) => KindTy::Type,
             Res::Module(..) => KindTy::Module,
             Res::Generic(..) => KindTy::Generic,
+            Res::Trait(..) => KindTy::Trait,
+            Res::Method(..) => KindTy::Fn,
         }
     }
 
@@ -85,6 +100,8 @@ impl Res {
             Res::Type(id) => &p.type_name(id),
             Res::Generic(id) => &p.generics[id].name,
             Res::Module(id) => &p.modules[id].name,
+            Res::Trait(id) => &p.traits[id].name,
+            Res::Method(m) => &p.traits[m.tr].fns[m.idx].name,
         };
         format!("{} '{}'", self.kind(), name)
     }
@@ -131,6 +148,19 @@ impl IdentID {
             _ => None,
         }
     }
+    pub fn callee(&self, p: &UProgram) -> Option<Callee> {
+        match &p.idents[self].status {
+            IdentStatus::Res(Res::Fn(i)) => Some(Callee::Fn(i.clone())),
+            IdentStatus::Res(Res::Method(m)) => Some(Callee::Dyn(m.clone())),
+            _ => None,
+        }
+    }
+    pub fn trai(&self, p: &UProgram) -> Option<TraitID> {
+        match p.idents[self].status {
+            IdentStatus::Res(Res::Trait(id)) => Some(id),
+            _ => None,
+        }
+    }
     pub fn struc<'a>(&self, p: &'a UProgram) -> Option<&'a StructInst> {
         match &p.idents[self].status {
             IdentStatus::Res(Res::Struct(i)) => Some(&i),
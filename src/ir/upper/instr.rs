@@ -21,11 +21,17 @@ impl ResStage for Unresolved {
 pub struct Resolved;
 impl ResStage for Resolved {
     type Var = VarID;
-    type Func = FnInst;
+    type Func = Callee;
     type Struct = StructInst;
     type Type = TypeID;
 }
 
+#[derive(Debug, Clone)]
+pub enum Callee {
+    Fn(FnInst),
+    Dyn(DynMethod),
+}
+
 pub enum UInstruction<S: ResStage = Unresolved> {
     Mv {
         dst: S::Var,
@@ -73,6 +79,93 @@ pub enum UInstruction<S: ResStage = Unresolved> {
     },
     Break,
     Continue,
+    /// fills the array `dst` with `elems` in order
+    Array {
+        dst: S::Var,
+        elems: Vec<S::Var>,
+    },
+    /// `dst` becomes a reference to element `idx` of the array or slice `src`;
+    /// if it's out of bounds, `oob` is printed and the program aborts
+    Index {
+        dst: S::Var,
+        src: S::Var,
+        idx: S::Var,
+        oob: DataID,
+    },
+    /// `dst` becomes the slice `src[start..end]` of an array or slice
+    SliceRange {
+        dst: S::Var,
+        src: S::Var,
+        start: Option<S::Var>,
+        end: Option<S::Var>,
+        oob: DataID,
+    },
+    /// `dst` becomes the number of elements in the array or slice `src`
+    Len {
+        dst: S::Var,
+        src: S::Var,
+    },
+    /// layout queries are answered once generics are known, during lowering
+    SizeOf {
+        dst: S::Var,
+        ty: TypeID,
+    },
+    AlignOf {
+        dst: S::Var,
+        ty: TypeID,
+    },
+    /// `lhs` and `rhs` have to be the same number type, whose signedness picks the
+    /// instructions; comparisons give a bool, everything else gives that type
+    BinOp {
+        dst: S::Var,
+        op: BinOp,
+        lhs: S::Var,
+        rhs: S::Var,
+    },
+    /// `dst = src as T`, where `dst` already has type `T`; numbers can become any other
+    /// number, bools can become numbers, and references can become numbers and back
+    Cast {
+        dst: S::Var,
+        src: S::Var,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl BinOp {
+    pub fn is_cmp(&self) -> bool {
+        matches!(
+            self,
+            Self::Lt | Self::Gt | Self::Le | Self::Ge | Self::Eq | Self::Ne
+        )
+    }
+
+    pub fn str(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::Le => "<=",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
 }
 
 pub struct UInstrInst<S: ResStage = Unresolved> {
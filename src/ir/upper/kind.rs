@@ -2,7 +2,7 @@
 
 use super::*;
 use crate::{
-    common::FileSpan,
+    common::{FileSpan, LintLevels},
     ir::{Len, ID},
 };
 use std::{
@@ -21,6 +21,8 @@ pub type StructID = ID<UStruct>;
 pub type DataID = ID<UData>;
 pub type ModID = ID<UModule>;
 pub type InstrID = ID<UInstrInst>;
+pub type TraitID = ID<UTrait>;
+pub type ImplID = ID<UImpl>;
 
 pub type VarRes = URes<VarID>;
 pub type TypeRes = URes<VarID>;
@@ -32,19 +34,72 @@ pub struct UFunc {
     pub gargs: Vec<GenericID>,
     pub ret: TypeRes,
     pub instructions: Vec<InstrID>,
+    /// module the body was written in, used for privacy checks
+    pub module: ModID,
+    /// from `#[allow(..)]` and friends, on this fn or the ones it's in
+    pub lints: LintLevels,
+    pub attrs: Vec<Attr>,
+}
+
+/// built-in attributes that stick around after lowering; `cfg` and the lint ones are
+/// handled while lowering
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attr {
+    /// `#[entry]`, used as the entry point instead of `main`
+    Entry,
+    /// `#[test]`
+    Test,
+    /// `#[repr(C)]`, which promises the struct is laid out the way C would lay it out
+    ReprC,
 }
 
 pub struct StructField {
     pub ty: TypeRes,
     pub origin: Origin,
-    // pub vis: Visibility
+    pub vis: Visibility,
+    pub lints: LintLevels,
 }
 
 pub struct UStruct {
     pub name: String,
     pub origin: Origin,
-    pub fields: HashMap<String, StructField>,
+    /// in declaration order, which is also the order they're laid out in
+    pub fields: Vec<(String, StructField)>,
     pub gargs: Vec<GenericID>,
+    pub module: ModID,
+    /// marked `copy`, so it's copied instead of moved
+    pub copy: bool,
+    pub lints: LintLevels,
+    pub attrs: Vec<Attr>,
+}
+
+impl UStruct {
+    pub fn field(&self, name: &str) -> Option<&StructField> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, f)| f)
+    }
+}
+
+pub struct UTrait {
+    pub name: String,
+    pub origin: Origin,
+    pub fns: Vec<UTraitFn>,
+}
+
+pub struct UTraitFn {
+    pub name: String,
+    pub origin: Origin,
+    pub args: Vec<TypeID>,
+    pub ret: TypeID,
+}
+
+pub struct UImpl {
+    pub trait_: IdentID,
+    pub ty: TypeID,
+    pub origin: Origin,
+    pub fns: Vec<FnID>,
+    /// impl fns in the order of the trait's fns; this is the vtable layout.
+    /// empty until the impl has been checked against its trait
+    pub methods: Vec<FnID>,
 }
 
 pub struct UGeneric {
@@ -89,7 +144,14 @@ pub struct UModule {
 #[derive(Clone)]
 pub struct Member {
     pub id: MemberID,
-    // pub visibility: Visibility
+    pub vis: Visibility,
+}
+
+/// private items can only be accessed from their own module and its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
 }
 
 #[derive(Clone)]
@@ -99,10 +161,22 @@ pub enum MemberID {
     Var(VarID),
     Module(ModID),
     Type(TypeDef),
+    Trait(TraitID),
+    Use(UseDef),
+}
+
+/// `import a::b::c as d`; resolving `d` continues down `path` from `module`
+#[derive(Clone)]
+pub struct UseDef {
+    pub module: ModID,
+    /// innermost name first, like `IdentStatus::Unres`
+    pub path: Vec<MemberIdent>,
 }
 
 #[derive(Clone)]
 pub struct TypeDef {
+    /// alias name, shown in diagnostics instead of the aliased type
+    pub name: String,
     pub gargs: Vec<GenericID>,
     pub ty: TypeID,
 }
@@ -115,6 +189,8 @@ impl MemberID {
             MemberID::Var(_) => KindTy::Var,
             MemberID::Module(_) => KindTy::Module,
             MemberID::Type(_) => KindTy::Type,
+            MemberID::Trait(_) => KindTy::Trait,
+            MemberID::Use(_) => KindTy::Import,
         }
     }
     pub fn display_str(&self, p: &UProgram) -> String {
@@ -123,7 +199,9 @@ impl MemberID {
             MemberID::Fn(id) => &p.fns[id].name,
             MemberID::Struct(id) => &p.structs[id].name,
             MemberID::Module(id) => &p.modules[id].name,
-            MemberID::Type(def) => &p.type_name(def.ty),
+            MemberID::Type(def) => &def.name,
+            MemberID::Trait(id) => &p.traits[id].name,
+            MemberID::Use(u) => &u.path[0].name,
         };
         format!("{} '{}'", self.kind(), name)
     }
@@ -145,6 +223,8 @@ pub enum KindTy {
     Fn,
     Module,
     Generic,
+    Trait,
+    Import,
 }
 
 impl Display for KindTy {
@@ -156,6 +236,8 @@ impl Display for KindTy {
             KindTy::Struct => "struct",
             KindTy::Module => "module",
             KindTy::Generic => "generic",
+            KindTy::Trait => "trait",
+            KindTy::Import => "import",
         })
     }
 }
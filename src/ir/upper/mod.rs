@@ -5,6 +5,7 @@ mod ty;
 mod resolve;
 mod error;
 mod ident;
+mod analysis;
 
 use super::*;
 
@@ -15,3 +16,4 @@ pub use ty::*;
 pub use error::*;
 pub use resolve::*;
 pub use ident::*;
+pub use analysis::*;
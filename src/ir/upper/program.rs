@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::{HashMap, HashSet};
 
 pub struct UProgram {
     pub fns: Vec<UFunc>,
@@ -6,6 +7,8 @@ pub struct UProgram {
     pub modules: Vec<UModule>,
     pub data: Vec<UData>,
     pub generics: Vec<UGeneric>,
+    pub traits: Vec<UTrait>,
+    pub impls: Vec<UImpl>,
     pub vars: Vec<UVar>,
     pub idents: Vec<UIdent>,
     pub types: Vec<Type>,
@@ -14,6 +17,10 @@ pub struct UProgram {
     pub unres_idents: Vec<IdentID>,
     pub unres_instrs: Vec<(FnID, InstrID)>,
     pub tc: TypeCache,
+    /// module that `main` is looked up in
+    pub entry: Option<ModID>,
+    /// imports (module, name) that some path went through
+    pub used_imports: HashSet<(ModID, String)>,
 }
 
 pub struct TypeCache {
@@ -35,12 +42,16 @@ impl UProgram {
             structs: Vec::new(),
             types: Vec::new(),
             generics: Vec::new(),
+            traits: Vec::new(),
+            impls: Vec::new(),
             data: Vec::new(),
             modules: Vec::new(),
             instrs: Vec::new(),
             unres_idents: Vec::new(),
             unres_instrs: Vec::new(),
             tc,
+            entry: None,
+            used_imports: HashSet::new(),
         }
     }
 
@@ -84,6 +95,83 @@ impl UProgram {
         push_id(&mut self.modules, m)
     }
 
+    /// defines a module along with the fn holding its top level statements
+    pub fn new_module(&mut self, name: String, parent: Option<ModID>, origin: Origin) -> ModID {
+        let f = UFunc {
+            name: name.clone(),
+            args: Vec::new(),
+            instructions: Vec::new(),
+            gargs: Vec::new(),
+            ret: self.tc.unit,
+            origin,
+            // the module is defined right after its fn
+            module: ModID::new(self.modules.len()),
+            lints: Default::default(),
+            attrs: Vec::new(),
+        };
+        let func = self.def_fn(f);
+        self.def_module(UModule {
+            name,
+            members: HashMap::new(),
+            parent,
+            func,
+        })
+    }
+
+    pub fn crate_root(&self, mut id: ModID) -> ModID {
+        while let Some(parent) = self.modules[id].parent {
+            id = parent;
+        }
+        id
+    }
+
+    /// eg. `crate::util::inner`
+    pub fn module_path(&self, id: ModID) -> String {
+        match self.modules[id].parent {
+            Some(parent) => format!("{}::{}", self.module_path(parent), self.modules[id].name),
+            None => self.modules[id].name.clone(),
+        }
+    }
+
+    pub fn def_trait(&mut self, t: UTrait) -> TraitID {
+        push_id(&mut self.traits, t)
+    }
+
+    pub fn def_impl(&mut self, i: UImpl) -> ImplID {
+        push_id(&mut self.impls, i)
+    }
+
+    pub fn var_ty(&self, id: VarID) -> Option<TypeID> {
+        let ty = match self.vars[id].ty {
+            VarTy::Res(t) => t,
+            VarTy::Ident(i) => match self.idents[i].status {
+                IdentStatus::Res(Res::Type(t)) => t,
+                _ => return None,
+            },
+        };
+        clean_type(&self.types, ty)
+    }
+
+    /// if this type is a reference to a trait object, returns the trait
+    pub fn dyn_trait(&self, ty: TypeID) -> Option<TraitID> {
+        let &Type::Ref(inner) = &self.types[clean_type(&self.types, ty)?] else {
+            return None;
+        };
+        match &self.types[clean_type(&self.types, inner)?] {
+            Type::Dyn(id) => id.trai(self),
+            _ => None,
+        }
+    }
+
+    pub fn find_impl(&self, tr: TraitID, ty: TypeID) -> Option<ImplID> {
+        let ty = clean_type(&self.types, ty)?;
+        self.impls.iter().enumerate().find_map(|(i, im)| {
+            let ity = clean_type(&self.types, im.ty)?;
+            (im.trait_.trai(self) == Some(tr) && same_type(&self.types, ity, ty))
+                .then(|| ImplID::new(i))
+        })
+    }
+
     pub fn res_ty(&self, i: IdentID) -> Option<TypeID> {
         self.idents[i].status;
     }
@@ -107,11 +195,18 @@ impl UProgram {
             }
             Type::Ref(t) => format!("{}&", self.type_name(t)),
             Type::Bits(size) => format!("b{}", size),
+            Type::Int(int) => int.name(),
+            Type::Bool => "bool".to_string(),
+            Type::Char => "char".to_string(),
             Type::Array(t, len) => format!("[{}; {len}]", self.type_name(t)),
             Type::Unit => "()".to_string(),
             Type::Slice(t) => format!("&[{}]", self.type_name(t)),
             Type::Infer => "{inferred}".to_string(),
             Type::Generic(id) => self.generics[id].name.clone(),
+            Type::Dyn(id) => match id.trai(self) {
+                Some(tr) => format!("dyn {}", self.traits[tr].name),
+                None => "dyn {unknown}".to_string(),
+            },
             Type::Deref(t) => format!("{}^", self.type_name(t)),
             Type::Error => "{error}".to_string(),
             Type::Ptr(id) => self.type_name(id),
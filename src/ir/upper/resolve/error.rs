@@ -1,13 +1,14 @@
-use crate::common::{CompilerMsg, CompilerOutput};
+use crate::common::{CompilerMsg, CompilerOutput, Lint, LintLevels};
 
 use super::{
-    IdentStatus, KindTy, MemberTy, Origin, Res, ResBase, StructID, Type, TypeID, UProgram,
+    clean_type, BinOp, FnID, IdentStatus, KindTy, MemberID, MemberTy, ModID, Origin, Res, ResBase,
+    StructID, TraitID, Type, TypeID, UProgram, VarID,
 };
 
 pub fn report_errs(p: &UProgram, output: &mut CompilerOutput, mut errs: Vec<ResErr>) {
     for ident in &p.idents {
         match &ident.status {
-            IdentStatus::Unres { path, base } => {
+            IdentStatus::Unres { path, base, .. } => {
                 let mem = path.last().unwrap();
                 errs.push(ResErr::UnknownMember {
                     ty: mem.ty,
@@ -53,9 +54,53 @@ pub fn report_errs(p: &UProgram, output: &mut CompilerOutput, mut errs: Vec<ResE
                     origin,
                 ));
             }
+            ResErr::CannotIndex { origin, ty } => {
+                output.err(CompilerMsg::new(
+                    format!("Cannot index into type '{}'", p.type_name(ty)),
+                    origin,
+                ));
+            }
+            ResErr::IndexType { origin, ty } => {
+                output.err(CompilerMsg::new(
+                    format!("Indices must be integers; found '{}'", p.type_name(ty)),
+                    origin,
+                ));
+            }
+            ResErr::NotNumber { origin, op, ty } => {
+                output.err(CompilerMsg::new(
+                    format!("Cannot use '{}' on type '{}'", op.str(), p.type_name(ty)),
+                    origin,
+                ));
+            }
+            ResErr::MixedSign {
+                origin,
+                op,
+                lhs,
+                rhs,
+            } => {
+                output.err(CompilerMsg::new(
+                    format!(
+                        "Cannot use '{}' on '{}' and '{}'; signed and unsigned integers can't be mixed without a cast",
+                        op.str(),
+                        p.type_name(lhs),
+                        p.type_name(rhs)
+                    ),
+                    origin,
+                ));
+            }
+            ResErr::InvalidCast { origin, from, to } => {
+                output.err(CompilerMsg::new(
+                    format!(
+                        "Cannot cast type '{}' to '{}'; only numbers, bools and references can be cast",
+                        p.type_name(from),
+                        p.type_name(to)
+                    ),
+                    origin,
+                ));
+            }
             ResErr::CondType { origin, ty } => {
                 output.err(CompilerMsg::new(
-                    format!("Condition types must be '64'; found '{}'", p.type_name(ty)),
+                    format!("Condition types must be 'bool'; found '{}'", p.type_name(ty)),
                     origin,
                 ));
             }
@@ -80,6 +125,73 @@ pub fn report_errs(p: &UProgram, output: &mut CompilerOutput, mut errs: Vec<ResE
                     origin,
                 ));
             }
+            ResErr::PrivateMember {
+                origin,
+                mem,
+                module,
+            } => output.err(CompilerMsg::new(
+                format!(
+                    "{} is private to module '{}'",
+                    mem.display_str(p),
+                    p.modules[module].name
+                ),
+                origin,
+            )),
+            ResErr::UseCycle { origin, chain } => {
+                let chain: Vec<_> = chain
+                    .iter()
+                    .map(|(m, name)| format!("{}::{name}", p.module_path(*m)))
+                    .collect();
+                output.err(CompilerMsg::new(
+                    format!("Import cycle: {}", chain.join(" -> ")),
+                    origin,
+                ))
+            }
+            ResErr::PrivateField { origin, id, name } => {
+                let struc = &p.structs[id];
+                output.err(CompilerMsg::new(
+                    format!(
+                        "Field '{name}' of struct '{}' is private to module '{}'",
+                        struc.name, p.modules[struc.module].name
+                    ),
+                    origin,
+                ));
+            }
+            ResErr::ArgCount {
+                origin,
+                expected,
+                found,
+            } => output.err(CompilerMsg::new(
+                format!("Expected {expected} arguments, found {found}"),
+                origin,
+            )),
+            ResErr::MissingTraitFn { origin, tr, name } => output.err(CompilerMsg::new(
+                format!(
+                    "Missing function '{name}' in impl of trait '{}'",
+                    p.traits[tr].name
+                ),
+                origin,
+            )),
+            ResErr::UnknownTraitFn { origin, tr, name } => output.err(CompilerMsg::new(
+                format!(
+                    "Function '{name}' is not a member of trait '{}'",
+                    p.traits[tr].name
+                ),
+                origin,
+            )),
+            ResErr::TraitFnSig { origin, tr, idx } => {
+                let tf = &p.traits[tr].fns[idx];
+                output.err(CompilerMsg::new(
+                    format!(
+                        "Function '{}' does not match trait '{}': expected fn({}) -> {}",
+                        tf.name,
+                        p.traits[tr].name,
+                        p.type_list_str(tf.args.iter().cloned()),
+                        p.type_name(tf.ret)
+                    ),
+                    origin,
+                ))
+            }
             ResErr::NoReturn { fid } => output.err(CompilerMsg::new(
                 format!("Function must return a value"),
                 p.fns[fid].origin,
@@ -113,6 +225,74 @@ pub fn report_errs(p: &UProgram, output: &mut CompilerOutput, mut errs: Vec<ResE
                 format!("Unknown {ty} {name} of {}", parent.display_str(p)),
                 origin,
             )),
+            ResErr::BorrowOutlives {
+                borrow,
+                origin,
+                var,
+            } => output.err(cause_err(
+                format!(
+                    "'{}' does not live long enough; it's borrowed here and used after its scope ends",
+                    p.vars[var].name
+                ),
+                borrow,
+                origin,
+            )),
+            ResErr::BorrowedWrite {
+                borrow,
+                origin,
+                var,
+            } => output.err(cause_err(
+                format!(
+                    "Cannot assign to '{}' while it's borrowed",
+                    p.vars[var].name
+                ),
+                borrow,
+                origin,
+            )),
+            ResErr::ReturnLocalRef {
+                borrow,
+                origin,
+                var,
+            } => output.err(cause_err(
+                format!(
+                    "Cannot return a reference to local variable '{}'",
+                    p.vars[var].name
+                ),
+                borrow,
+                origin,
+            )),
+            ResErr::Uninit { origin, var } => output.err(CompilerMsg::new(
+                format!(
+                    "'{}' might be used before it's initialized",
+                    p.vars[var].name
+                ),
+                origin,
+            )),
+            ResErr::UseAfterMove { moved, origin, var } => output.err(cause_err(
+                format!("'{}' is moved here and used after", p.vars[var].name),
+                moved,
+                origin,
+            )),
+            ResErr::CopyField { origin, id, name } => output.err(CompilerMsg::new(
+                format!(
+                    "Struct '{}' is copy, but its field '{name}' isn't",
+                    p.structs[id].name
+                ),
+                origin,
+            )),
+            ResErr::ReprField {
+                origin,
+                id,
+                name,
+                ty,
+            } => output.err(CompilerMsg::new(
+                format!(
+                    "Struct '{}' is repr(C), but its field '{name}' has type '{}', which C doesn't have",
+                    p.structs[id].name,
+                    p.type_name(ty)
+                ),
+                origin,
+            )),
         }
     }
     for var in &p.vars {
@@ -154,13 +334,58 @@ pub enum ResErr {
         origin: Origin,
         ty: TypeID,
     },
+    CannotIndex {
+        origin: Origin,
+        ty: TypeID,
+    },
+    IndexType {
+        origin: Origin,
+        ty: TypeID,
+    },
     CondType {
         origin: Origin,
         ty: TypeID,
     },
+    NotNumber {
+        origin: Origin,
+        op: BinOp,
+        ty: TypeID,
+    },
+    /// an operator with a signed int on one side and an unsigned one on the other
+    MixedSign {
+        origin: Origin,
+        op: BinOp,
+        lhs: TypeID,
+        rhs: TypeID,
+    },
+    InvalidCast {
+        origin: Origin,
+        from: TypeID,
+        to: TypeID,
+    },
     NoReturn {
         fid: usize,
     },
+    ArgCount {
+        origin: Origin,
+        expected: usize,
+        found: usize,
+    },
+    MissingTraitFn {
+        origin: Origin,
+        tr: TraitID,
+        name: String,
+    },
+    UnknownTraitFn {
+        origin: Origin,
+        tr: TraitID,
+        name: String,
+    },
+    TraitFnSig {
+        origin: Origin,
+        tr: TraitID,
+        idx: usize,
+    },
     BadControlFlow {
         op: ControlFlowOp,
         origin: Origin,
@@ -175,12 +400,162 @@ pub enum ResErr {
         id: StructID,
         name: String,
     },
+    PrivateMember {
+        origin: Origin,
+        mem: MemberID,
+        module: ModID,
+    },
+    PrivateField {
+        origin: Origin,
+        id: StructID,
+        name: String,
+    },
+    /// the imports followed, ending with the one that was already followed
+    UseCycle {
+        origin: Origin,
+        chain: Vec<(ModID, String)>,
+    },
     Type {
         dst: TypeID,
         src: TypeID,
         errs: Vec<TypeMismatch>,
         origin: Origin,
     },
+    /// `borrow` is where the reference was taken
+    BorrowOutlives {
+        borrow: Origin,
+        origin: Origin,
+        var: VarID,
+    },
+    BorrowedWrite {
+        borrow: Origin,
+        origin: Origin,
+        var: VarID,
+    },
+    ReturnLocalRef {
+        borrow: Origin,
+        origin: Origin,
+        var: VarID,
+    },
+    Uninit {
+        origin: Origin,
+        var: VarID,
+    },
+    /// `moved` is where the value was moved out
+    UseAfterMove {
+        moved: Origin,
+        origin: Origin,
+        var: VarID,
+    },
+    CopyField {
+        origin: Origin,
+        id: StructID,
+        name: String,
+    },
+    ReprField {
+        origin: Origin,
+        id: StructID,
+        name: String,
+        ty: TypeID,
+    },
+}
+
+/// things that are allowed but probably mistakes; each belongs to a lint
+pub enum ResWarn {
+    Unreachable {
+        fid: FnID,
+        origin: Origin,
+    },
+    UnusedVar {
+        fid: FnID,
+        origin: Origin,
+        var: VarID,
+    },
+    UnusedFn {
+        fid: FnID,
+    },
+    UnusedImport {
+        module: ModID,
+        origin: Origin,
+        name: String,
+    },
+    UnusedField {
+        origin: Origin,
+        id: StructID,
+        name: String,
+    },
+    /// `prev` is where the shadowed var was declared
+    Shadowing {
+        fid: FnID,
+        origin: Origin,
+        prev: Origin,
+        var: VarID,
+    },
+}
+
+impl ResWarn {
+    pub fn lint(&self) -> Lint {
+        match self {
+            Self::Unreachable { .. } => Lint::Unreachable,
+            Self::UnusedVar { .. }
+            | Self::UnusedFn { .. }
+            | Self::UnusedImport { .. }
+            | Self::UnusedField { .. } => Lint::Unused,
+            Self::Shadowing { .. } => Lint::Shadowing,
+        }
+    }
+
+    /// levels set on the item this is in
+    pub fn levels<'a>(&self, p: &'a UProgram) -> &'a LintLevels {
+        match self {
+            &Self::Unreachable { fid, .. }
+            | &Self::UnusedVar { fid, .. }
+            | &Self::UnusedFn { fid }
+            | &Self::Shadowing { fid, .. } => &p.fns[fid].lints,
+            &Self::UnusedImport { module, .. } => &p.fns[p.modules[module].func].lints,
+            Self::UnusedField { id, name, .. } => {
+                &p.structs[*id].field(name).expect("field exists").lints
+            }
+        }
+    }
+}
+
+pub fn report_warns(p: &UProgram, output: &mut CompilerOutput, warns: Vec<ResWarn>) {
+    for warn in warns {
+        let msg = match &warn {
+            &ResWarn::Unreachable { origin, .. } => {
+                CompilerMsg::new("Unreachable code".to_string(), origin)
+            }
+            &ResWarn::UnusedVar { origin, var, .. } => {
+                CompilerMsg::new(format!("Unused variable '{}'", p.vars[var].name), origin)
+            }
+            &ResWarn::UnusedFn { fid } => {
+                let f = &p.fns[fid];
+                CompilerMsg::new(format!("Function '{}' is never used", f.name), f.origin)
+            }
+            ResWarn::UnusedImport { origin, name, .. } => {
+                CompilerMsg::new(format!("Unused import '{name}'"), *origin)
+            }
+            ResWarn::UnusedField { origin, id, name } => CompilerMsg::new(
+                format!(
+                    "Field '{name}' of struct '{}' is never read",
+                    p.structs[*id].name
+                ),
+                *origin,
+            ),
+            &ResWarn::Shadowing {
+                origin, prev, var, ..
+            } => cause_err(
+                format!(
+                    "'{}' shadows a variable that's still in scope",
+                    p.vars[var].name
+                ),
+                prev,
+                origin,
+            ),
+        };
+        output.lint(warn.lint(), warn.levels(p), msg);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -204,10 +579,25 @@ pub struct TypeMismatch {
     pub src: TypeID,
 }
 
+/// points at what caused the error (a borrow or move) first, then where it went wrong
+fn cause_err(msg: String, cause: Origin, origin: Origin) -> CompilerMsg {
+    let mut msg = CompilerMsg::new(msg, cause);
+    msg.spans.push(origin);
+    msg
+}
+
 pub fn type_assign_err(p: &UProgram, dst: TypeID, src: TypeID) -> String {
-    format!(
+    let mut msg = format!(
         "Cannot assign type {} to {}",
         p.type_name(src),
         p.type_name(dst)
-    )
+    );
+    let ints = (clean_type(&p.types, dst), clean_type(&p.types, src));
+    if let (Some(dst), Some(src)) = ints
+        && let (Type::Int(d), Type::Int(s)) = (&p.types[dst], &p.types[src])
+        && d.signed != s.signed
+    {
+        msg.push_str(" (signed and unsigned integers can't be mixed without a cast)");
+    }
+    msg
 }
@@ -18,68 +18,30 @@ impl UProgram {
                     IdentStatus::Cooked => self.idents[i].status = IdentStatus::Cooked,
                 }
             }
-            let status = &mut self.idents[i].status;
-            // TOOD: there are some clones here that shouldn't be needed
-            let IdentStatus::Unres { path, base } = status else {
-                continue;
-            };
+            // taken out while it's worked on, so the rest of the program can be looked at
+            let mut status = std::mem::replace(&mut self.idents[i].status, IdentStatus::Cooked);
+            resolve_res |= self.resolve_ident(i, &mut status, errs);
+            self.idents[i].status = status;
+        }
+        resolve_res
+    }
 
-            while let Some(mem) = path.pop() {
-                let res = match base {
-                    ResBase::Unvalidated(u) => {
-                        match u.validate(
-                            &self.fns,
-                            &self.structs,
-                            &self.generics,
-                            &mut self.types,
-                            errs,
-                        ) {
-                            Ok(res) => res,
-                            Err(err) => {
-                                *status = IdentStatus::Failed(err);
-                                continue 'main;
-                            }
-                        }
-                    }
-                    ResBase::Validated(res) => res.clone(),
-                };
-                *base = match (res, mem.ty) {
-                    (Res::Module(id), MemberTy::Member) => {
-                        let Some(m) = self.modules[id].members.get(&mem.name) else {
-                            self.unres_idents.push(i);
-                            continue 'main;
-                        };
-                        ResBase::Unvalidated(MemRes {
-                            mem: m.clone(),
-                            origin: mem.origin,
-                            gargs: mem.gargs,
-                        })
-                    }
-                    (Res::Var(id), MemberTy::Field) => {
-                        // trait resolution here
-                        let Some(&child) = self.vars[id].children.get(&mem.name) else {
-                            self.unres_idents.push(i);
-                            continue 'main;
-                        };
-                        ResBase::Unvalidated(MemRes {
-                            mem: Member {
-                                id: MemberID::Var(child),
-                            },
-                            origin: mem.origin,
-                            gargs: mem.gargs,
-                        })
-                    }
-                    _ => {
-                        *status = IdentStatus::Failed(Some(ResErr::UnknownMember {
-                            origin: mem.origin,
-                            ty: mem.ty,
-                            name: mem.name.clone(),
-                            parent: base.clone(),
-                        }));
-                        continue 'main;
-                    }
-                };
-            }
+    /// follows the rest of an ident's path; anything that can't be resolved yet puts the
+    /// ident back in `unres_idents`
+    fn resolve_ident(
+        &mut self,
+        i: IdentID,
+        status: &mut IdentStatus,
+        errs: &mut Vec<ResErr>,
+    ) -> ResolveRes {
+        // TOOD: there are some clones here that shouldn't be needed
+        let IdentStatus::Unres { path, base, scope } = status else {
+            return ResolveRes::Finished;
+        };
+
+        // the imports followed so far, to catch ones that (indirectly) import themselves
+        let mut uses: Vec<(ModID, String)> = Vec::new();
+        while let Some(mem) = path.pop() {
             let res = match base {
                 ResBase::Unvalidated(u) => {
                     match u.validate(
@@ -92,16 +54,131 @@ impl UProgram {
                         Ok(res) => res,
                         Err(err) => {
                             *status = IdentStatus::Failed(err);
-                            continue 'main;
+                            return ResolveRes::Finished;
                         }
                     }
                 }
                 ResBase::Validated(res) => res.clone(),
             };
-            *status = IdentStatus::Res(res);
-            resolve_res = ResolveRes::Unfinished;
+            *base = match (res, mem.ty) {
+                (Res::Module(id), MemberTy::Member) => {
+                    let Some(m) = self.modules[id].members.get(&mem.name) else {
+                        // keep the path intact so it can be retried / reported
+                        path.push(mem);
+                        self.unres_idents.push(i);
+                        return ResolveRes::Finished;
+                    };
+                    if m.vis == Visibility::Private && !can_see(&self.modules, *scope, id) {
+                        *status = IdentStatus::Failed(Some(ResErr::PrivateMember {
+                            origin: mem.origin,
+                            mem: m.id.clone(),
+                            module: id,
+                        }));
+                        return ResolveRes::Finished;
+                    }
+                    if let MemberID::Use(u) = &m.id {
+                        self.used_imports.insert((id, mem.name.clone()));
+                        let used = (id, mem.name.clone());
+                        if let Some(start) = uses.iter().position(|u| *u == used) {
+                            let mut chain = uses.split_off(start);
+                            chain.push(used);
+                            *status = IdentStatus::Failed(Some(ResErr::UseCycle {
+                                origin: mem.origin,
+                                chain,
+                            }));
+                            return ResolveRes::Finished;
+                        }
+                        uses.push(used);
+                        path.extend(u.path.iter().cloned());
+                        *base = ResBase::Validated(Res::Module(u.module));
+                        continue;
+                    }
+                    ResBase::Unvalidated(MemRes {
+                        mem: m.clone(),
+                        origin: mem.origin,
+                        gargs: mem.gargs,
+                    })
+                }
+                (Res::Var(id), MemberTy::Field) => {
+                    if let Some(method) = self.dyn_method(id, &mem.name) {
+                        *base = ResBase::Validated(Res::Method(method));
+                        continue;
+                    }
+                    if let Some(ty) = self.auto_deref_ty(id)
+                        && let Type::Struct(si) = &self.types[ty]
+                        && let struc = &self.structs[si.id]
+                        && let Some(field) = struc.field(&mem.name)
+                        && field.vis == Visibility::Private
+                        && !can_see(&self.modules, *scope, struc.module)
+                    {
+                        *status = IdentStatus::Failed(Some(ResErr::PrivateField {
+                            origin: mem.origin,
+                            id: si.id,
+                            name: mem.name.clone(),
+                        }));
+                        return ResolveRes::Finished;
+                    }
+                    let Some(child) = self.field_var(id, &mem.name, mem.origin) else {
+                        path.push(mem);
+                        self.unres_idents.push(i);
+                        return ResolveRes::Finished;
+                    };
+                    ResBase::Unvalidated(MemRes {
+                        mem: Member {
+                            id: MemberID::Var(child),
+                            vis: Visibility::Public,
+                        },
+                        origin: mem.origin,
+                        gargs: mem.gargs,
+                    })
+                }
+                _ => {
+                    *status = IdentStatus::Failed(Some(ResErr::UnknownMember {
+                        origin: mem.origin,
+                        ty: mem.ty,
+                        name: mem.name.clone(),
+                        parent: base.clone(),
+                    }));
+                    return ResolveRes::Finished;
+                }
+            };
         }
-        resolve_res
+        let res = match base {
+            ResBase::Unvalidated(u) => {
+                match u.validate(
+                    &self.fns,
+                    &self.structs,
+                    &self.generics,
+                    &mut self.types,
+                    errs,
+                ) {
+                    Ok(res) => res,
+                    Err(err) => {
+                        *status = IdentStatus::Failed(err);
+                        return ResolveRes::Finished;
+                    }
+                }
+            }
+            ResBase::Validated(res) => res.clone(),
+        };
+        *status = IdentStatus::Res(res);
+        ResolveRes::Unfinished
+    }
+
+    /// the type of `id` with any references looked through, like field access does
+    fn auto_deref_ty(&self, id: VarID) -> Option<TypeID> {
+        let mut ty = self.var_ty(id)?;
+        while let Type::Ref(inner) = self.types[ty] {
+            ty = clean_type(&self.types, inner)?;
+        }
+        Some(ty)
+    }
+
+    /// `name` as a method of the trait object `id` points to, if it is one
+    fn dyn_method(&self, id: VarID, name: &str) -> Option<DynMethod> {
+        let tr = self.dyn_trait(self.var_ty(id)?)?;
+        let idx = self.traits[tr].fns.iter().position(|f| f.name == name)?;
+        Some(DynMethod { recv: id, tr, idx })
     }
 }
 
@@ -162,15 +239,64 @@ impl MemRes {
                 no_gargs()?;
                 Res::Module(id)
             }
+            &MemberID::Trait(id) => {
+                no_gargs()?;
+                Res::Trait(id)
+            }
+            // spliced into the path while resolving, so never validated directly
+            MemberID::Use(_) => compiler_error(),
             MemberID::Type(def) => {
                 validate_gargs(&def.gargs, &self.gargs, generics, types, errs, self.origin)?;
-                inst_typedef(def, &self.gargs, types);
-                Res::Type(def.ty)
+                Res::Type(inst_typedef(def, &self.gargs, types))
             }
         })
     }
 }
 
+impl UProgram {
+    /// field vars are made on first use, once the type of `id` is known;
+    /// references are looked through with an implicit deref
+    fn field_var(&mut self, id: VarID, name: &str, origin: Origin) -> Option<VarID> {
+        if let Some(&child) = self.vars[id].children.get(name) {
+            return Some(child);
+        }
+        let ty = self.var_ty(id)?;
+        let fty = match self.types[ty].clone() {
+            Type::Slice(elem) => self.slice_field_ty(elem, name)?,
+            Type::Struct(si) => {
+                let struc = &self.structs[si.id];
+                let field = struc.field(name)?;
+                let gmap = inst_gmap(&struc.gargs, &si.gargs);
+                inst_type(field.ty, &mut self.types, &gmap)
+            }
+            Type::Ref(_) => {
+                let derf = match self.vars[id].children.get(DEREF_VAR) {
+                    Some(&d) => d,
+                    None => {
+                        let dty = self.def_ty(Type::Deref(ty));
+                        self.child_var(id, DEREF_VAR, dty, origin)
+                    }
+                };
+                return self.field_var(derf, name, origin);
+            }
+            _ => return None,
+        };
+        Some(self.child_var(id, name, fty, origin))
+    }
+
+    fn child_var(&mut self, parent: VarID, name: &str, ty: TypeID, origin: Origin) -> VarID {
+        let child = self.def_var(UVar {
+            name: name.to_string(),
+            origin,
+            ty: VarTy::Res(ty),
+            parent: Some(parent),
+            children: Default::default(),
+        });
+        self.vars[parent].children.insert(name.to_string(), child);
+        child
+    }
+}
+
 pub fn validate_gargs(
     dst: &[GenericID],
     src: &[TypeID],
@@ -193,3 +319,15 @@ pub fn validate_gargs(
     }
     Ok(())
 }
+
+/// whether code in `from` can see private members of `owner`
+pub fn can_see(modules: &[UModule], from: ModID, owner: ModID) -> bool {
+    let mut cur = Some(from);
+    while let Some(m) = cur {
+        if m == owner {
+            return true;
+        }
+        cur = modules[m].parent;
+    }
+    false
+}
@@ -75,7 +75,7 @@ fn inst_type_(
     gmap: &HashMap<GenericID, TypeID>,
 ) -> Option<TypeID> {
     let ty = match types[id].clone() {
-        Type::Bits(_) => return None,
+        Type::Bits(_) | Type::Int(_) | Type::Bool | Type::Char => return None,
         Type::Struct(struct_ty) => Type::Struct(StructInst {
             id: struct_ty.id,
             gargs: inst_all(&struct_ty.gargs, types, gmap)?,
@@ -90,6 +90,7 @@ fn inst_type_(
         Type::Unit => return None,
         Type::Generic(gid) => return gmap.get(&gid).map(|id| Some(*id)).unwrap_or_else(|| None),
         Type::Infer => Type::Infer,
+        Type::Dyn(_) => return None,
         Type::Deref(id) => Type::Deref(inst_type_(id, types, gmap)?),
         Type::Ptr(id) => Type::Ptr(inst_type_(id, types, gmap)?),
         Type::Error => return None,
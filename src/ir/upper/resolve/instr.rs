@@ -19,6 +19,8 @@ impl UProgram {
                 generics: &self.generics,
                 data: &self.data,
                 modules: &self.modules,
+                traits: &self.traits,
+                impls: &self.impls,
             },
             errs,
         };
@@ -52,12 +54,31 @@ pub fn resolve_instr<'a>(
             }
             data.res::<UVar>(dst);
             match fi {
-                Ok(fi) => {
+                Ok(Callee::Fn(fi)) => {
                     let f = &data.s.fns[fi.id];
                     for (&src, &dst) in args.iter().zip(&f.args) {
                         data.s.constraints.push(UResEvent::AssignVVI { dst, src });
                     }
                 }
+                Ok(Callee::Dyn(m)) => {
+                    let f = &data.s.traits[m.tr].fns[m.idx];
+                    let (fargs, ret) = (f.args.clone(), f.ret);
+                    // the receiver is passed implicitly as the first argument
+                    if fargs.len() != args.len() + 1 {
+                        data.errs.push(ResErr::ArgCount {
+                            origin: instr.origin,
+                            expected: fargs.len() - 1,
+                            found: args.len(),
+                        });
+                        return ResolveRes::Finished;
+                    }
+                    let mut res = ResolveRes::Finished;
+                    for (src, &dst) in args.iter().zip(&fargs[1..]) {
+                        res |= data.match_types::<Type, UVar>(dst, src, src);
+                    }
+                    res |= data.match_types::<UVar, Type>(dst, ret, dst);
+                    return res;
+                }
                 Err(r) => return r,
             }
             ResolveRes::Finished
@@ -120,6 +141,20 @@ pub fn resolve_instr<'a>(
                     });
                 }
             }
+            let module = data.s.fns[fi].module;
+            for (name, field) in &st.fields {
+                if fields.contains_key(name)
+                    && field.vis == Visibility::Private
+                    && !can_see(data.s.modules, module, st.module)
+                {
+                    let origin = dst.origin(data);
+                    data.errs.push(ResErr::PrivateField {
+                        origin,
+                        id: sid,
+                        name: name.clone(),
+                    });
+                }
+            }
             for (name, _) in fields {
                 if !used.contains(name) {
                     let origin = dst.origin(data);
@@ -132,12 +167,11 @@ pub fn resolve_instr<'a>(
             }
         }
         UInstruction::If { cond, body } => {
-            if let Some(ty) = data.res_var_ty(cond, ctx) {
-                if !matches!(ty.0, RType::Bits(64)) {
-                    let id = ty.1;
-                    let origin = cond.origin(data);
-                    data.errs.push(ResErr::CondType { origin, ty: id });
-                }
+            if let Ok(id) = data.res_var_ty(*cond)
+                && !matches!(data.types[id], Type::Bool | Type::Infer)
+            {
+                let origin = cond.origin(data);
+                data.errs.push(ResErr::CondType { origin, ty: id });
             }
             for i in body {
                 resolve_instr(
@@ -162,6 +196,94 @@ pub fn resolve_instr<'a>(
                 );
             }
         }
+        UInstruction::Array { dst, elems } => {
+            let &Type::Array(elem, _) = &data.types[data.res_var_ty(dst)?] else {
+                compiler_error()
+            };
+            for src in elems {
+                res |= data.match_types::<Type, UVar>(elem, src, src);
+            }
+        }
+        UInstruction::Index { dst, src, idx, .. } => {
+            let elem = data.elem_ty(src)?;
+            data.check_index(idx)?;
+            let &Type::Ref(dst_ty) = &data.types[data.res_var_ty(dst)?] else {
+                compiler_error()
+            };
+            res |= data.match_types(dst_ty, elem, dst);
+        }
+        UInstruction::SliceRange {
+            dst,
+            src,
+            start,
+            end,
+            ..
+        } => {
+            let elem = data.elem_ty(src)?;
+            for i in start.iter().chain(end) {
+                data.check_index(i)?;
+            }
+            let &Type::Slice(dst_ty) = &data.types[data.res_var_ty(dst)?] else {
+                compiler_error()
+            };
+            res |= data.match_types(dst_ty, elem, dst);
+        }
+        // dst is always 64 bits
+        UInstruction::Len { src, .. } => {
+            data.elem_ty(src)?;
+        }
+        // dst is always 64 bits and the type can be anything sized
+        UInstruction::SizeOf { .. } | UInstruction::AlignOf { .. } => {}
+        UInstruction::BinOp { dst, op, lhs, rhs } => {
+            let l = data.res_var_ty(*lhs)?;
+            let r = data.res_var_ty(*rhs)?;
+            if data.is_infer(l) || data.is_infer(r) {
+                return ResolveRes::Unfinished;
+            }
+            // bools and chars can only be compared for equality
+            let eq = matches!(op, BinOp::Eq | BinOp::Ne);
+            for id in [l, r] {
+                let ty = &data.types[id];
+                if !(ty.is_number() || eq && matches!(ty, Type::Bool | Type::Char)) {
+                    data.errs.push(ResErr::NotNumber {
+                        origin: instr.origin,
+                        op: *op,
+                        ty: id,
+                    });
+                    return ResolveRes::Finished;
+                }
+            }
+            if let (Type::Int(a), Type::Int(b)) = (&data.types[l], &data.types[r])
+                && a.signed != b.signed
+            {
+                data.errs.push(ResErr::MixedSign {
+                    origin: instr.origin,
+                    op: *op,
+                    lhs: l,
+                    rhs: r,
+                });
+                return ResolveRes::Finished;
+            }
+            res |= data.match_types::<UVar, UVar>(lhs, rhs, rhs);
+            // comparisons give a bool, which the lowering already typed dst as
+            if !op.is_cmp() {
+                res |= data.match_types::<UVar, UVar>(dst, lhs, dst);
+            }
+        }
+        UInstruction::Cast { dst, src } => {
+            let from = data.res_var_ty(*src)?;
+            let to = data.res_var_ty(*dst)?;
+            if data.is_infer(from) || data.is_infer(to) {
+                return ResolveRes::Unfinished;
+            }
+            if !data.can_cast(from, to) {
+                data.errs.push(ResErr::InvalidCast {
+                    origin: instr.origin,
+                    from,
+                    to,
+                });
+            }
+        }
         UInstruction::Break => {
             if !ctx.breakable {
                 data.errs.push(ResErr::BadControlFlow {
@@ -180,3 +302,53 @@ pub fn resolve_instr<'a>(
         }
     }
 }
+
+impl ResData<'_> {
+    /// element type of the array or slice in `src`
+    fn elem_ty(&mut self, src: &IdentID) -> Result<TypeID, ResolveRes> {
+        let id = self.res_var_ty(*src)?;
+        match self.types[id] {
+            Type::Array(t, _) | Type::Slice(t) => Ok(t),
+            _ => {
+                let origin = src.origin(self);
+                self.errs.push(ResErr::CannotIndex { origin, ty: id });
+                Err(ResolveRes::Finished)
+            }
+        }
+    }
+
+    fn is_infer(&self, ty: TypeID) -> bool {
+        matches!(self.types[ty], Type::Infer)
+    }
+
+    /// numbers cast to any number, bools to numbers, and references to and from numbers;
+    /// trait object references carry a vtable too, so they can't be
+    fn can_cast(&self, from: TypeID, to: TypeID) -> bool {
+        if same_type(self.types, from, to) {
+            return true;
+        }
+        let thin_ref = |id: TypeID| match self.types[id] {
+            Type::Ref(t) => {
+                clean_type(self.types, t).is_none_or(|t| !matches!(self.types[t], Type::Dyn(_)))
+            }
+            _ => false,
+        };
+        match (self.types[from].is_number(), self.types[to].is_number()) {
+            (true, true) => true,
+            // but nothing becomes a bool, since which numbers are true is up to the code
+            (false, true) if matches!(self.types[from], Type::Bool) => true,
+            (true, false) => thin_ref(to),
+            (false, true) => thin_ref(from),
+            (false, false) => false,
+        }
+    }
+
+    fn check_index(&mut self, idx: &IdentID) -> Result<(), ResolveRes> {
+        let id = self.res_var_ty(*idx)?;
+        if !matches!(self.types[id], Type::Bits(64) | Type::Int(_)) {
+            let origin = idx.origin(self);
+            self.errs.push(ResErr::IndexType { origin, ty: id });
+        }
+        Ok(())
+    }
+}
@@ -47,6 +47,16 @@ pub fn match_types(data: &mut ResData, dst: TypeID, src: TypeID) -> MatchRes {
         //     let src = src_args.into_iter().chain(once(src_ret));
         //     match_all(data, dst, src)
         // }
+        (Type::Bits(a), Type::Bits(b)) if a == b => MatchRes::Finished,
+        (Type::Int(a), Type::Int(b)) if a == b => MatchRes::Finished,
+        // raw bits can stand in for an int of the same size, which is what lets untyped
+        // literals be used as an `i64` or `u64`
+        (Type::Int(int), Type::Bits(bits)) | (Type::Bits(bits), Type::Int(int))
+            if int.bits == bits =>
+        {
+            MatchRes::Finished
+        }
+        (Type::Bool, Type::Bool) | (Type::Char, Type::Char) => MatchRes::Finished,
         (Type::Ref(dest), Type::Ref(src)) => match_types(data, dest, src),
         (Type::Slice(dest), Type::Slice(src)) => match_types(data, dest, src),
         (Type::Array(dest, dlen), Type::Array(src, slen)) => {
@@ -56,6 +66,27 @@ pub fn match_types(data: &mut ResData, dst: TypeID, src: TypeID) -> MatchRes {
                 error()
             }
         }
+        (Type::Dyn(dest), src_ty) => {
+            let Some(dest) = data.trait_res(dest)? else {
+                return MatchRes::Finished;
+            };
+            if let Type::Dyn(src) = src_ty {
+                let Some(src) = data.trait_res(src)? else {
+                    return MatchRes::Finished;
+                };
+                return if dest == src { MatchRes::Finished } else { error() };
+            }
+            // concrete types coerce into trait objects of any trait they implement
+            let implemented = data.s.impls.iter().any(|im| {
+                matches!(data.s.idents[im.trait_].status, IdentStatus::Res(Res::Trait(id)) if id == dest)
+                    && same_type(data.types, im.ty, src)
+            });
+            if implemented {
+                MatchRes::Finished
+            } else {
+                error()
+            }
+        }
         _ => error(),
     }
 }
@@ -86,6 +117,14 @@ fn match_all(
 }
 
 impl<'a> ResData<'a> {
+    /// Ok(None) means the ident failed and was already reported
+    fn trait_res(&self, id: IdentID) -> Result<Option<TraitID>, MatchRes> {
+        match &self.s.idents[id].status {
+            &IdentStatus::Res(Res::Trait(id)) => Ok(Some(id)),
+            IdentStatus::Unres { .. } | IdentStatus::Ref(..) => Err(MatchRes::Unfinished),
+            _ => Ok(None),
+        }
+    }
     pub fn match_types(
         &mut self,
         dst: impl MaybeTypeID,
@@ -13,8 +13,10 @@ mod ident;
 mod instantiate;
 mod instr;
 mod matc;
+mod trai;
 
 pub use error::*;
+use ident::can_see;
 use instantiate::*;
 
 impl UProgram {
@@ -27,19 +29,40 @@ impl UProgram {
             res |= self.resolve_idents(&mut errs);
             res |= self.resolve_instrs(&mut errs);
         }
+        self.resolve_impls(&mut errs);
+        let mut warns = Vec::new();
         for (fid, f) in self.fns.iter().enumerate() {
-            // this currently works bc expressions create temporary variables
-            // although you can't do things like loop {return 3} (need to analyze control flow)
+            let flow = check_flow(self, f);
             if let Some(ty) = self.res_ty(f.ret)
                 && self.types[ty] != Type::Unit
-                && f.instructions
-                    .last()
-                    .is_none_or(|i| !matches!(self.instrs[i].i, UInstruction::Ret { .. }))
+                && flow.falls_through
             {
                 errs.push(ResErr::NoReturn { fid });
             }
+            warns.extend(
+                flow.unreachable
+                    .into_iter()
+                    .map(|origin| ResWarn::Unreachable {
+                        fid: fid.into(),
+                        origin,
+                    }),
+            );
+        }
+        // these assume everything resolved
+        if errs.is_empty() {
+            check_unused(self, &mut warns);
+            check_copy_structs(self, &mut errs);
+            check_repr_structs(self, &mut errs);
+            for (fid, f) in self.fns.iter().enumerate() {
+                check_init(self, f, &mut errs);
+                check_borrows(self, f, &mut errs);
+                if self.in_entry_package(f.module) {
+                    check_shadowing(self, fid.into(), f, &mut warns);
+                }
+            }
         }
         report_errs(self, output, errs);
+        report_warns(self, output, warns);
     }
 }
 
@@ -56,6 +79,8 @@ struct Sources<'a> {
     generics: &'a [UGeneric],
     data: &'a [UData],
     modules: &'a [UModule],
+    traits: &'a [UTrait],
+    impls: &'a [UImpl],
 }
 
 struct ResData<'a> {
@@ -188,13 +213,14 @@ pub trait ResKind {
 }
 
 impl ResKind for UFunc {
-    type Res = FnInst;
+    type Res = Callee;
     fn ty() -> KindTy {
         KindTy::Fn
     }
     fn from_res(res: Res, _: &mut Vec<Type>, _: &mut Sources, _: Origin) -> Result<Self::Res, Res> {
         match res {
-            Res::Fn(fi) => Ok(fi),
+            Res::Fn(fi) => Ok(Callee::Fn(fi)),
+            Res::Method(m) => Ok(Callee::Dyn(m)),
             _ => Err(res),
         }
     }
@@ -0,0 +1,85 @@
+use super::*;
+
+impl UProgram {
+    /// checks every impl against its trait and fills in its vtable order
+    pub fn resolve_impls(&mut self, errs: &mut Vec<ResErr>) {
+        for iid in 0..self.impls.len() {
+            let im = &self.impls[iid];
+            let Some(tr) = im.trait_.trai(self) else {
+                // non-trait idents are reported as kind mismatches
+                continue;
+            };
+            let t = &self.traits[tr];
+            let mut methods = Vec::new();
+            for (i, tf) in t.fns.iter().enumerate() {
+                let Some(&fid) = im.fns.iter().find(|&&f| self.fns[f].name == tf.name) else {
+                    errs.push(ResErr::MissingTraitFn {
+                        origin: im.origin,
+                        tr,
+                        name: tf.name.clone(),
+                    });
+                    continue;
+                };
+                let f = &self.fns[fid];
+                let args_match = f.args.len() == tf.args.len()
+                    && f.args.iter().zip(&tf.args).all(|(&a, &ta)| {
+                        self.var_ty(a)
+                            .is_none_or(|a| self.impl_ty_matches(tr, im.ty, a, ta))
+                    });
+                let ret_matches = self
+                    .res_ty(f.ret)
+                    .is_none_or(|r| self.impl_ty_matches(tr, im.ty, r, tf.ret));
+                if !args_match || !ret_matches {
+                    errs.push(ResErr::TraitFnSig {
+                        origin: f.origin,
+                        tr,
+                        idx: i,
+                    });
+                }
+                methods.push(fid);
+            }
+            for &fid in &im.fns {
+                let f = &self.fns[fid];
+                if !t.fns.iter().any(|tf| tf.name == f.name) {
+                    errs.push(ResErr::UnknownTraitFn {
+                        origin: f.origin,
+                        tr,
+                        name: f.name.clone(),
+                    });
+                }
+            }
+            if methods.len() == t.fns.len() {
+                self.impls[iid].methods = methods;
+            }
+        }
+    }
+
+    /// whether `ty` from an impl of `tr` for `self_ty` matches `expected` from the trait,
+    /// where the trait's `dyn Trait` stands for the implementing type
+    fn impl_ty_matches(&self, tr: TraitID, self_ty: TypeID, ty: TypeID, expected: TypeID) -> bool {
+        let (Some(ty), Some(expected)) = (
+            clean_type(&self.types, ty),
+            clean_type(&self.types, expected),
+        ) else {
+            // already reported
+            return true;
+        };
+        let all = |a: &[TypeID], b: &[TypeID]| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(&a, &b)| self.impl_ty_matches(tr, self_ty, a, b))
+        };
+        match (&self.types[ty], &self.types[expected]) {
+            (_, Type::Dyn(id)) if id.trai(self) == Some(tr) => same_type(&self.types, ty, self_ty),
+            (&Type::Ref(a), &Type::Ref(b)) | (&Type::Slice(a), &Type::Slice(b)) => {
+                self.impl_ty_matches(tr, self_ty, a, b)
+            }
+            (&Type::Array(a, alen), &Type::Array(b, blen)) => {
+                alen == blen && self.impl_ty_matches(tr, self_ty, a, b)
+            }
+            (Type::Struct(a), Type::Struct(b)) => a.id == b.id && all(&a.gargs, &b.gargs),
+            _ => same_type(&self.types, ty, expected),
+        }
+    }
+}
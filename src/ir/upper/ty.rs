@@ -1,4 +1,4 @@
-use super::{FnID, GenericID, Len, ResolveRes, StructID, TypeID, UProgram, VarID};
+use super::{Attr, FnID, GenericID, IdentID, Len, ResolveRes, StructID, TypeID, UProgram, VarID};
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct FieldRef {
@@ -20,9 +20,23 @@ pub struct FnInst {
     pub gargs: Vec<TypeID>,
 }
 
+/// `i8`..`i64` and `u8`..`u64`; signedness decides how values are extended when loaded,
+/// and which comparison and division instructions get used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntTy {
+    pub signed: bool,
+    pub bits: u32,
+}
+
 #[derive(Clone, PartialEq)]
 pub enum Type {
+    /// raw bits with no meaning attached; matches an int of the same size
     Bits(u32),
+    Int(IntTy),
+    /// `true` or `false`, stored in a byte as 1 or 0
+    Bool,
+    /// a unicode scalar value, stored in 32 bits
+    Char,
     Struct(StructInst),
     // this can be added for constraints later (F: fn(...) -> ...)
     // Fn { args: Vec<TypeID>, ret: TypeID },
@@ -30,6 +44,9 @@ pub enum Type {
     FnInst(FnInst),
     Ref(TypeID),
     Slice(TypeID),
+    /// unsized trait object; only usable behind a reference,
+    /// which is then a (data pointer, vtable pointer) pair
+    Dyn(IdentID),
     Array(TypeID, Len),
     Unit,
     Infer,
@@ -75,6 +92,113 @@ impl Type {
     }
 }
 
+impl Type {
+    /// whether arithmetic and comparisons work on this
+    pub fn is_number(&self) -> bool {
+        matches!(self, Self::Bits(_) | Self::Int(_))
+    }
+}
+
+impl IntTy {
+    pub const WIDTHS: [u32; 4] = [8, 16, 32, 64];
+
+    /// the type for a builtin name like `i32` or `u8`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let signed = match name.as_bytes().first()? {
+            b'i' => true,
+            b'u' => false,
+            _ => return None,
+        };
+        let bits = Self::WIDTHS
+            .into_iter()
+            .find(|b| name[1..] == b.to_string())?;
+        Some(Self { signed, bits })
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}{}", if self.signed { 'i' } else { 'u' }, self.bits)
+    }
+
+    /// whether `val` (as written in a literal, so never negative) fits
+    pub fn fits(&self, val: u64) -> bool {
+        let bits = if self.signed {
+            self.bits - 1
+        } else {
+            self.bits
+        };
+        bits >= 64 || val < 1 << bits
+    }
+}
+
+/// name of the child var that a reference is implicitly dereferenced into;
+/// unlike fields, it gets its own stack slot and is loaded on every use
+pub const DEREF_VAR: &str = "^";
+
+// slices are a pointer to the first element followed by the length;
+// offsets are in bits
+pub const SLICE_PTR_OFFSET: Len = 0;
+pub const SLICE_LEN_OFFSET: Len = 64;
+
+/// offset of a builtin slice field (`ptr` or `len`)
+pub fn slice_field_offset(name: &str) -> Option<Len> {
+    match name {
+        "ptr" => Some(SLICE_PTR_OFFSET),
+        "len" => Some(SLICE_LEN_OFFSET),
+        _ => None,
+    }
+}
+
+impl UProgram {
+    /// type of a builtin field of a slice of `elem`
+    pub fn slice_field_ty(&mut self, elem: TypeID, name: &str) -> Option<TypeID> {
+        Some(match name {
+            "ptr" => self.def_ty(Type::Ref(elem)),
+            "len" => self.def_ty(Type::Bits(64)),
+            _ => return None,
+        })
+    }
+
+    /// whether values of this type are copied instead of moved;
+    /// structs have to opt in with `struct Name: copy`
+    pub fn is_copy(&self, ty: TypeID) -> bool {
+        let Some(ty) = clean_type(&self.types, ty) else {
+            return true;
+        };
+        match &self.types[ty] {
+            Type::Bits(_)
+            | Type::Int(_)
+            | Type::Bool
+            | Type::Char
+            | Type::Ref(_)
+            | Type::Slice(_)
+            | Type::FnInst(_)
+            | Type::Unit => true,
+            &Type::Array(elem, _) => self.is_copy(elem),
+            Type::Struct(si) => self.structs[si.id].copy,
+            Type::Error => true,
+            _ => false,
+        }
+    }
+
+    /// whether C has an equivalent for `ty`, so it can go in a `#[repr(C)]` struct;
+    /// generics are only known once they're filled in, so they're let through
+    pub fn is_c_type(&self, ty: TypeID) -> bool {
+        let Some(ty) = clean_type(&self.types, ty) else {
+            return true;
+        };
+        match &self.types[ty] {
+            Type::Bits(b) => b.is_power_of_two() && (8..=64).contains(b),
+            Type::Int(_) | Type::Bool | Type::Char | Type::Generic(_) => true,
+            // fat pointers have no C equivalent
+            Type::Ref(_) => self.dyn_trait(ty).is_none(),
+            &Type::Array(elem, _) => self.is_c_type(elem),
+            Type::Struct(si) => self.structs[si.id].attrs.contains(&Attr::ReprC),
+            Type::Error => true,
+            _ => false,
+        }
+    }
+}
+
 pub fn clean_type(types: &[Type], id: TypeID) -> Option<TypeID> {
     match &types[id] {
         &Type::Ptr(id) => clean_type(types, id),
@@ -99,3 +223,24 @@ pub fn resolved_type(types: &[Type], id: TypeID) -> Result<TypeID, ResolveRes> {
         _ => Ok(id),
     }
 }
+
+/// structural equality; used where type ids can differ for the same type (eg. impl lookup)
+pub fn same_type(types: &[Type], a: TypeID, b: TypeID) -> bool {
+    let (Some(a), Some(b)) = (clean_type(types, a), clean_type(types, b)) else {
+        return false;
+    };
+    if a == b {
+        return true;
+    }
+    let all = |a: &[TypeID], b: &[TypeID]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(&a, &b)| same_type(types, a, b))
+    };
+    match (&types[a], &types[b]) {
+        (Type::Struct(a), Type::Struct(b)) => a.id == b.id && all(&a.gargs, &b.gargs),
+        (Type::FnInst(a), Type::FnInst(b)) => a.id == b.id && all(&a.gargs, &b.gargs),
+        (&Type::Ref(a), &Type::Ref(b)) => same_type(types, a, b),
+        (&Type::Slice(a), &Type::Slice(b)) => same_type(types, a, b),
+        (&Type::Array(a, alen), &Type::Array(b, blen)) => alen == blen && same_type(types, a, b),
+        (a, b) => a == b,
+    }
+}
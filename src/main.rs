@@ -8,11 +8,12 @@
 
 pub const FILE_EXT: &str = "lang";
 
-use common::{CompilerOutput, SrcFile};
+use common::{CompilerOutput, LintConfig};
 use ir::{LProgram, UProgram};
-use parser::{Import, Imports, PModule, ParserCtx};
+use manifest::MANIFEST_FILE;
+use parser::{load_std, search_roots, Loader, PackageLoader, Source};
+use stdlib::STD_NAME;
 use std::{
-    collections::HashSet,
     fs::{create_dir_all, OpenOptions},
     io::stdout,
     os::unix::fs::OpenOptionsExt,
@@ -23,71 +24,62 @@ use std::{
 mod common;
 mod compiler;
 mod ir;
+mod manifest;
 mod parser;
+mod stdlib;
 mod util;
 
 fn main() {
     let file = std::env::args_os().nth(1);
+    let args: Vec<String> = std::env::args().skip(2).collect();
     // TODO: professional arg parsing
-    let gdb = std::env::args().nth(2).is_some_and(|a| a == "--debug");
-    let asm = std::env::args().nth(2).is_some_and(|a| a == "--asm");
+    let gdb = args.iter().any(|a| a == "--debug");
+    let asm = args.iter().any(|a| a == "--asm");
+    let no_std = args.iter().any(|a| a == "--no-std");
+    let lints = match LintConfig::from_args(&args) {
+        Ok(lints) => lints,
+        Err(e) => {
+            println!("error: {e}");
+            std::process::exit(1);
+        }
+    };
     if let Some(path) = file {
         let path = PathBuf::from(path);
-        run_file(&path, gdb, asm);
+        run_file(&path, gdb, asm, no_std, lints);
     } else {
         run_stdin();
     }
 }
 
 impl UProgram {
-    pub fn from_path(path: &Path) -> (Self, CompilerOutput) {
-        let parent = path.parent().expect("bruh");
+    /// `path` can be a single file, or a package directory / manifest
+    pub fn from_path(path: &Path, no_std: bool, lints: LintConfig) -> (Self, CompilerOutput) {
         let mut program = Self::new();
         let mut output = CompilerOutput::new();
-
-        let mut imports = Imports::new();
-        imports.insert(Import(vec![path
-            .file_name()
-            .expect("bruh")
-            .to_str()
-            .expect("bruh")
-            .to_string()]));
-        let mut imported = HashSet::new();
-        let mut fid = 0;
-
-        while !imports.is_empty() {
-            let iter = std::mem::take(&mut imports);
-            for i in iter {
-                let import_path = &i.0;
-                if imported.contains(&i) {
-                    continue;
-                }
-                let mut file_path = parent.to_path_buf();
-                file_path.extend(import_path);
-                file_path.set_extension(FILE_EXT);
-                let text = std::fs::read_to_string(&file_path).expect("failed to read file");
-                output.file_map.insert(
-                    fid,
-                    SrcFile {
-                        path: file_path,
-                        text: text.clone(),
-                    },
-                );
-                let mut ctx = ParserCtx::new(fid, text.as_str(), &mut output);
-                fid += 1;
-                let res = PModule::parse(&mut ctx);
-                // println!("Parsed:");
-                // println!("{:#?}", res.node);
-                res.lower(import_path.clone(), &mut program, &mut imports, &mut output);
-                imported.insert(i);
+        output.lints = lints;
+        let std = (!no_std).then(|| load_std(&mut program, &mut output));
+        if path.is_dir() || path.file_name().is_some_and(|n| n == MANIFEST_FILE) {
+            let mut loader = PackageLoader {
+                std,
+                ..Default::default()
+            };
+            program.entry = loader.load(path, &mut program, &mut output);
+        } else {
+            let parent = path.parent().expect("bruh");
+            let mut loader = Loader::new(search_roots(parent));
+            if let Some(std) = std {
+                loader.externs.insert(STD_NAME.to_string(), std);
             }
+            let entry = Source::File(path.to_path_buf());
+            let root = loader.load(entry, "crate".to_string(), &mut program, &mut output);
+            program.entry = Some(root);
         }
         (program, output)
     }
 }
 
-fn run_file(path: &Path, gdb: bool, asm: bool) {
-    let (mut program, mut output) = UProgram::from_path(path);
+fn run_file(path: &Path, gdb: bool, asm: bool, no_std: bool, lints: LintConfig) {
+    let (mut program, mut output) = UProgram::from_path(path, no_std, lints);
     program.resolve(&mut output);
     // println!("vars:");
     // for (id, def) in program.iter_vars() {
@@ -97,10 +89,17 @@ fn run_file(path: &Path, gdb: bool, asm: bool) {
     //     println!("{}:{id:?} = {:#?}", program.names.path(id), f);
     // }
     if !output.errs.is_empty() {
+        // includes lints denied with `-D`
         output.write_to(&mut stdout());
-        return;
+        std::process::exit(1);
     }
-    let program = LProgram::create(&program).expect("morir");
+    let program = match LProgram::create(&program) {
+        Ok(program) => program,
+        Err(e) => {
+            println!("error: {e}");
+            std::process::exit(1);
+        }
+    };
     let unlinked = compiler::compile(&program);
     if asm {
         println!("{:?}", unlinked);
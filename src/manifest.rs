@@ -0,0 +1,146 @@
+//! `lang.toml`; only the small part of toml we need is supported:
+//! `[section]` headers, `key = "string"`, `key = true` and `key = { path = "string" }`
+//!
+//! ```toml
+//! [package]
+//! name = "app"
+//! entry = "main.lang"
+//! no_std = false
+//!
+//! [dependencies]
+//! util = { path = "../util" }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "lang.toml";
+pub const DEFAULT_ENTRY: &str = "main.lang";
+
+pub struct Manifest {
+    /// directory the manifest is in; all paths are relative to it
+    pub dir: PathBuf,
+    pub name: String,
+    pub entry: PathBuf,
+    /// don't give the package access to `std`
+    pub no_std: bool,
+    pub deps: Vec<Dependency>,
+}
+
+pub struct Dependency {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+enum Section {
+    None,
+    Package,
+    Dependencies,
+}
+
+impl Manifest {
+    /// `path` can be the package directory or the manifest itself
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = if path.is_dir() {
+            path.join(MANIFEST_FILE)
+        } else {
+            path.to_path_buf()
+        };
+        let text = std::fs::read_to_string(&file)
+            .map_err(|e| format!("failed to read {}: {e}", file.display()))?;
+        let dir = file.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Self::parse(&text, dir).map_err(|e| format!("{}: {e}", file.display()))
+    }
+
+    pub fn parse(text: &str, dir: PathBuf) -> Result<Self, String> {
+        let mut section = Section::None;
+        let mut name = None;
+        let mut entry = None;
+        let mut no_std = false;
+        let mut deps = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |msg: &str| format!("line {}: {msg}", i + 1);
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.strip_suffix(']').ok_or_else(|| err("expected `]`"))?;
+                section = match header.trim() {
+                    "package" => Section::Package,
+                    "dependencies" => Section::Dependencies,
+                    other => return Err(err(&format!("unknown section '{other}'"))),
+                };
+                continue;
+            }
+            let (key, val) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`"))?;
+            let (key, val) = (key.trim(), val.trim());
+            match section {
+                Section::Package => match key {
+                    "name" => name = Some(string(val).ok_or_else(|| err("expected a string"))?),
+                    "entry" => {
+                        let val = string(val).ok_or_else(|| err("expected a string"))?;
+                        entry = Some(PathBuf::from(val));
+                    }
+                    "no_std" => no_std = boolean(val).ok_or_else(|| err("expected a bool"))?,
+                    _ => return Err(err(&format!("unknown package key '{key}'"))),
+                },
+                Section::Dependencies => {
+                    let path = string(val)
+                        .or_else(|| path_table(val))
+                        .ok_or_else(|| err("expected a path or `{ path = \"...\" }`"))?;
+                    deps.push(Dependency {
+                        name: key.to_string(),
+                        path: dir.join(path),
+                    });
+                }
+                Section::None => return Err(err("expected a section header first")),
+            }
+        }
+        let name = name.ok_or("missing package name")?;
+        let entry = dir.join(entry.unwrap_or(PathBuf::from(DEFAULT_ENTRY)));
+        Ok(Self {
+            dir,
+            name,
+            entry,
+            no_std,
+            deps,
+        })
+    }
+}
+
+/// everything before a `#` that isn't inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_str = !in_str,
+            '#' if !in_str => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+fn string(val: &str) -> Option<String> {
+    let val = val.strip_prefix('"')?.strip_suffix('"')?;
+    Some(val.to_string())
+}
+
+fn boolean(val: &str) -> Option<bool> {
+    match val {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn path_table(val: &str) -> Option<String> {
+    let inner = val.strip_prefix('{')?.strip_suffix('}')?;
+    let (key, val) = inner.split_once('=')?;
+    if key.trim() != "path" {
+        return None;
+    }
+    string(val.trim())
+}
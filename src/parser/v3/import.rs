@@ -1,5 +1,361 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
-#[derive(Clone, Eq, PartialEq, Hash)]
-pub struct Import(pub Vec<String>);
-pub type Imports = HashSet<Import>;
+use crate::{
+    common::{CompilerMsg, CompilerOutput, FileSpan, SrcFile},
+    ir::{Member, MemberID, ModID, UProgram, Visibility},
+    manifest::Manifest,
+    stdlib::{STD_FILES, STD_NAME},
+    FILE_EXT,
+};
+
+/// extra directories to look for imported files in, separated by `:`
+pub const PATH_VAR: &str = "LANG_PATH";
+
+/// `dir` followed by the directories in `PATH_VAR`
+pub fn search_roots(dir: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![dir.to_path_buf()];
+    if let Some(var) = std::env::var_os(PATH_VAR) {
+        roots.extend(std::env::split_paths(&var));
+    }
+    roots
+}
+
+use super::{PModule, ParserCtx};
+
+/// a path some module wants to use; the loader checks whether a prefix of it is a file
+pub struct Import {
+    pub path: Vec<String>,
+    /// module the path starts from
+    pub module: ModID,
+    pub origin: FileSpan,
+}
+
+pub type Imports = Vec<Import>;
+
+/// where a module's text comes from
+pub enum Source {
+    File(PathBuf),
+    /// built into the compiler; the path is only for diagnostics
+    Embedded(PathBuf, &'static str),
+}
+
+impl Source {
+    fn read(self) -> (PathBuf, String) {
+        match self {
+            Source::File(path) => {
+                let text = std::fs::read_to_string(&path).expect("failed to read file");
+                (path, text)
+            }
+            Source::Embedded(path, text) => (path, text.to_string()),
+        }
+    }
+}
+
+/// loads the entry file and every file it (transitively) imports.
+/// `a::b` is looked for as `a/b.lang` in each search root, in order,
+/// and becomes the module `crate::a::b`
+pub struct Loader {
+    pub roots: Vec<PathBuf>,
+    /// files that are looked up before `roots`, as (path without extension, text)
+    pub embedded: &'static [(&'static str, &'static str)],
+    /// root modules of other packages, usable from anywhere by name
+    pub externs: HashMap<String, ModID>,
+    /// modules that stand for a file or directory, by path from the search root
+    modules: HashMap<Vec<String>, ModID>,
+    files: HashSet<ModID>,
+    /// which files each file imports, for cycle detection
+    deps: HashMap<ModID, Vec<(ModID, FileSpan)>>,
+}
+
+impl Loader {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            embedded: &[],
+            externs: HashMap::new(),
+            modules: HashMap::new(),
+            files: HashSet::new(),
+            deps: HashMap::new(),
+        }
+    }
+
+    /// loads a file and everything it imports under a new top level module
+    pub fn load(
+        &mut self,
+        entry: Source,
+        name: String,
+        p: &mut UProgram,
+        output: &mut CompilerOutput,
+    ) -> ModID {
+        let mut imports = Imports::new();
+        let root = self.load_file(entry, Vec::new(), None, p, &mut imports, output);
+        p.modules[root].name = name;
+        for (name, &id) in &self.externs {
+            p.modules[root].members.insert(
+                name.clone(),
+                Member {
+                    id: MemberID::Module(id),
+                    vis: Visibility::Private,
+                },
+            );
+        }
+        while let Some(import) = imports.pop() {
+            self.import(import, root, p, &mut imports, output);
+        }
+        self.check_cycles(p, output);
+        root
+    }
+
+    fn find_file(&self, path: &[String]) -> Option<Source> {
+        let name = path.join("/");
+        if let Some(&(_, text)) = self.embedded.iter().find(|(n, _)| *n == name) {
+            let mut file = PathBuf::from(format!("<builtin>/{name}"));
+            file.set_extension(FILE_EXT);
+            return Some(Source::Embedded(file, text));
+        }
+        self.roots.iter().find_map(|root| {
+            let mut file = root.join(&name);
+            file.set_extension(FILE_EXT);
+            file.is_file().then_some(Source::File(file))
+        })
+    }
+
+    fn import(
+        &mut self,
+        import: Import,
+        root: ModID,
+        p: &mut UProgram,
+        imports: &mut Imports,
+        output: &mut CompilerOutput,
+    ) {
+        if let Some(&id) = self.externs.get(&import.path[0]) {
+            p.modules[import.module]
+                .members
+                .entry(import.path[0].clone())
+                .or_insert(Member {
+                    id: MemberID::Module(id),
+                    vis: Visibility::Private,
+                });
+            return;
+        }
+        // longest prefix that's a file; anything after it is a member path
+        let Some(len) = (1..=import.path.len())
+            .rev()
+            .find(|&len| self.find_file(&import.path[..len]).is_some())
+        else {
+            // not a file, so it has to be resolvable from the module itself
+            return;
+        };
+        let Some(id) = self.module_for(&import.path[..len], root, p, imports, output) else {
+            return;
+        };
+        let from = self.file_of(p, import.module);
+        self.deps.entry(from).or_default().push((id, import.origin));
+        // make the first segment nameable where the import was written
+        if let Some(&first) = self.modules.get(&import.path[..1]) {
+            p.modules[import.module]
+                .members
+                .entry(import.path[0].clone())
+                .or_insert(Member {
+                    id: MemberID::Module(first),
+                    vis: Visibility::Private,
+                });
+        }
+    }
+
+    /// the module for a path, loading the file or making a directory module if needed
+    fn module_for(
+        &mut self,
+        path: &[String],
+        root: ModID,
+        p: &mut UProgram,
+        imports: &mut Imports,
+        output: &mut CompilerOutput,
+    ) -> Option<ModID> {
+        if let Some(&id) = self.modules.get(path) {
+            return Some(id);
+        }
+        let (name, dir) = path.split_last()?;
+        let parent = if dir.is_empty() {
+            root
+        } else {
+            self.module_for(dir, root, p, imports, output)?
+        };
+        let id = match self.find_file(path) {
+            Some(file) => self.load_file(file, path.to_vec(), Some(parent), p, imports, output),
+            None => {
+                let origin = p.fns[p.modules[parent].func].origin;
+                let id = p.new_module(name.clone(), Some(parent), origin);
+                self.modules.insert(path.to_vec(), id);
+                id
+            }
+        };
+        p.modules[parent].members.insert(
+            name.clone(),
+            Member {
+                id: MemberID::Module(id),
+                vis: Visibility::Public,
+            },
+        );
+        Some(id)
+    }
+
+    fn load_file(
+        &mut self,
+        file: Source,
+        path: Vec<String>,
+        parent: Option<ModID>,
+        p: &mut UProgram,
+        imports: &mut Imports,
+        output: &mut CompilerOutput,
+    ) -> ModID {
+        let (file, text) = file.read();
+        let fid = output.file_map.len();
+        output.file_map.insert(
+            fid,
+            SrcFile {
+                path: file,
+                text: text.clone(),
+            },
+        );
+        let mut ctx = ParserCtx::new(fid, text.as_str(), output);
+        let res = PModule::parse(&mut ctx);
+        let name = path.last().cloned().unwrap_or("crate".to_string());
+        let id = res.lower(name, parent, p, imports, output);
+        self.modules.insert(path, id);
+        self.files.insert(id);
+        id
+    }
+
+    /// the file an (inline) module was written in
+    fn file_of(&self, p: &UProgram, mut id: ModID) -> ModID {
+        while !self.files.contains(&id) {
+            id = p.modules[id].parent.expect("module outside of any file");
+        }
+        id
+    }
+
+    fn check_cycles(&self, p: &UProgram, output: &mut CompilerOutput) {
+        let mut done = HashSet::new();
+        let mut stack = Vec::new();
+        let mut files: Vec<_> = self.files.iter().copied().collect();
+        files.sort_by_key(|id| id.0);
+        for file in files {
+            self.visit(file, p, &mut stack, &mut done, output);
+        }
+    }
+
+    fn visit(
+        &self,
+        id: ModID,
+        p: &UProgram,
+        stack: &mut Vec<ModID>,
+        done: &mut HashSet<ModID>,
+        output: &mut CompilerOutput,
+    ) {
+        if done.contains(&id) {
+            return;
+        }
+        stack.push(id);
+        for &(dep, origin) in self.deps.get(&id).into_iter().flatten() {
+            if let Some(start) = stack.iter().position(|&m| m == dep) {
+                let chain: Vec<_> = stack[start..]
+                    .iter()
+                    .chain([&dep])
+                    .map(|&m| p.module_path(m))
+                    .collect();
+                output.err(CompilerMsg::new(
+                    format!("Import cycle: {}", chain.join(" -> ")),
+                    origin,
+                ));
+            } else {
+                self.visit(dep, p, stack, done, output);
+            }
+        }
+        stack.pop();
+        done.insert(id);
+    }
+}
+
+/// mounts the bundled standard library as the top level module `std`
+pub fn load_std(p: &mut UProgram, output: &mut CompilerOutput) -> ModID {
+    let mut loader = Loader::new(Vec::new());
+    loader.embedded = STD_FILES;
+    let (name, text) = STD_FILES[0];
+    let entry = Source::Embedded(PathBuf::from(format!("<builtin>/{name}.{FILE_EXT}")), text);
+    loader.load(entry, STD_NAME.to_string(), p, output)
+}
+
+/// loads packages from their manifests, each as its own top level module
+#[derive(Default)]
+pub struct PackageLoader {
+    /// given to every package that doesn't opt out
+    pub std: Option<ModID>,
+    /// by canonical package directory
+    loaded: HashMap<PathBuf, ModID>,
+    /// packages currently being loaded, for dependency cycles
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl PackageLoader {
+    pub fn load(
+        &mut self,
+        path: &Path,
+        p: &mut UProgram,
+        output: &mut CompilerOutput,
+    ) -> Option<ModID> {
+        let manifest = match Manifest::load(path) {
+            Ok(m) => m,
+            Err(e) => {
+                output.err(CompilerMsg::from_msg(e));
+                return None;
+            }
+        };
+        let key = manifest.dir.canonicalize().unwrap_or(manifest.dir.clone());
+        if let Some(&id) = self.loaded.get(&key) {
+            return Some(id);
+        }
+        if let Some(start) = self.stack.iter().position(|(dir, _)| *dir == key) {
+            let chain: Vec<_> = self.stack[start..]
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .chain([manifest.name.as_str()])
+                .collect();
+            output.err(CompilerMsg::from_msg(format!(
+                "Dependency cycle: {}",
+                chain.join(" -> ")
+            )));
+            return None;
+        }
+        self.stack.push((key.clone(), manifest.name.clone()));
+        let mut externs = HashMap::new();
+        for dep in &manifest.deps {
+            if let Some(id) = self.load(&dep.path, p, output) {
+                externs.insert(dep.name.clone(), id);
+            }
+        }
+        self.stack.pop();
+        if let Some(std) = self.std
+            && !manifest.no_std
+        {
+            externs.entry(STD_NAME.to_string()).or_insert(std);
+        }
+        if !manifest.entry.is_file() {
+            output.err(CompilerMsg::from_msg(format!(
+                "Entry file {} of package '{}' does not exist",
+                manifest.entry.display(),
+                manifest.name
+            )));
+            return None;
+        }
+        let dir = manifest.entry.parent().unwrap_or(&manifest.dir);
+        let mut loader = Loader::new(search_roots(dir));
+        loader.externs = externs;
+        let id = loader.load(Source::File(manifest.entry), manifest.name, p, output);
+        self.loaded.insert(key, id);
+        Some(id)
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    compiler::arch::riscv::Reg,
+    compiler::arch::riscv::{t0, t1, t2, Reg},
     ir::{
         arch::riscv64::RV64Instruction, AsmBlockArg, AsmBlockArgType, IdentID, Type, UInstruction,
     },
@@ -65,6 +65,30 @@ impl FnLowerable for PAsmBlock {
     }
 }
 
+impl FnLowerCtx<'_, '_> {
+    /// runs `instructions` with `inputs` in t0, t1, ... and evaluates to t0;
+    /// for arithmetic the IR has no instructions for yet
+    pub fn asm_op(&mut self, inputs: &[IdentID], instructions: Vec<RV64Instruction>) -> IdentID {
+        let mut args: Vec<_> = inputs
+            .iter()
+            .zip([t0, t1, t2])
+            .map(|(&var, reg)| AsmBlockArg {
+                var,
+                reg,
+                ty: AsmBlockArgType::In,
+            })
+            .collect();
+        let out = self.temp(Type::Bits(64));
+        args.push(AsmBlockArg {
+            var: out,
+            reg: t0,
+            ty: AsmBlockArgType::Out,
+        });
+        self.push(UInstruction::AsmBlock { instructions, args });
+        out
+    }
+}
+
 impl FnLowerable for PUAsmBlockArg {
     type Output = PLAsmBlockArg;
 
@@ -0,0 +1,152 @@
+use std::fmt::Display;
+
+use crate::{
+    common::{Lint, LintLevel, LintLevels},
+    ir::Attr,
+};
+
+use super::{CompilerMsg, ModuleLowerCtx, Node, PAttr, PAttrArg};
+
+/// names `#[cfg(..)]` is true for
+const CFG: [&str; 2] = ["riscv64", "linux"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrTarget {
+    Fn,
+    Struct,
+    Field,
+    Module,
+    Statement,
+}
+
+pub struct LoweredAttrs {
+    pub attrs: Vec<Attr>,
+    /// the levels around the item with its own lint attributes applied
+    pub lints: LintLevels,
+    /// false if a `#[cfg(..)]` turned the item off
+    pub enabled: bool,
+}
+
+impl ModuleLowerCtx<'_> {
+    /// checks the attributes on an item against the built-in ones
+    pub fn lower_attrs(&mut self, attrs: &[Node<PAttr>], target: AttrTarget) -> LoweredAttrs {
+        let mut res = LoweredAttrs {
+            attrs: Vec::new(),
+            lints: self.lints.clone(),
+            enabled: true,
+        };
+        for attr in attrs {
+            let Some(a) = attr.as_ref() else {
+                continue;
+            };
+            let Some(name) = a.name.as_ref() else {
+                continue;
+            };
+            let name = name.0.as_str();
+            if let Some(level) = LintLevel::from_attr(name) {
+                self.lint_attr(a, level, &mut res.lints);
+                continue;
+            }
+            let lowered = match name {
+                "cfg" => {
+                    if let Some(enabled) = self.cfg_attr(attr) {
+                        res.enabled &= enabled;
+                    }
+                    continue;
+                }
+                "entry" => Attr::Entry,
+                "test" => Attr::Test,
+                "inline" | "section" => {
+                    self.attr_err(attr, format!("Attribute '{name}' is not supported yet"));
+                    continue;
+                }
+                "repr" => match a.args.first().and_then(|a| a.as_ref()) {
+                    Some(PAttrArg::Ident(repr)) if a.args.len() == 1 && repr.0 == "C" => {
+                        Attr::ReprC
+                    }
+                    _ => {
+                        self.attr_err(attr, "Attribute 'repr' only supports #[repr(C)]");
+                        continue;
+                    }
+                },
+                _ => {
+                    self.attr_err(attr, format!("Unknown attribute '{name}'"));
+                    continue;
+                }
+            };
+            if lowered != Attr::ReprC && !a.args.is_empty() {
+                self.attr_err(attr, format!("Attribute '{name}' takes no arguments"));
+                continue;
+            }
+            let allowed = match lowered {
+                Attr::ReprC => AttrTarget::Struct,
+                _ => AttrTarget::Fn,
+            };
+            if target != allowed {
+                self.attr_err(
+                    attr,
+                    format!("Attribute '{name}' can only be put on {allowed}, not {target}"),
+                );
+                continue;
+            }
+            res.attrs.push(lowered);
+        }
+        res
+    }
+
+    /// `#[allow(..)]`, `#[warn(..)]` and `#[deny(..)]`; `warnings` means every lint
+    fn lint_attr(&mut self, attr: &PAttr, level: LintLevel, levels: &mut LintLevels) {
+        for arg in &attr.args {
+            let Some(PAttrArg::Ident(lint)) = arg.as_ref() else {
+                self.output.err(CompilerMsg::new(
+                    "Expected a lint name".to_string(),
+                    arg.origin,
+                ));
+                continue;
+            };
+            if lint.0 == "warnings" {
+                for l in Lint::ALL {
+                    levels.set(l, level);
+                }
+                continue;
+            }
+            match Lint::from_name(&lint.0) {
+                Some(l) => levels.set(l, level),
+                None => self.output.err(CompilerMsg::new(
+                    format!("Unknown lint '{}'", lint.0),
+                    arg.origin,
+                )),
+            }
+        }
+    }
+
+    /// whether the item should be compiled, or None if the attribute is malformed
+    fn cfg_attr(&mut self, attr: &Node<PAttr>) -> Option<bool> {
+        let a = attr.as_ref()?;
+        match a.args.first().and_then(|a| a.as_ref()) {
+            Some(PAttrArg::Ident(name)) if a.args.len() == 1 => {
+                Some(CFG.contains(&name.0.as_str()))
+            }
+            _ => {
+                self.attr_err(attr, "Attribute 'cfg' takes a name, like #[cfg(linux)]");
+                None
+            }
+        }
+    }
+
+    fn attr_err(&mut self, attr: &Node<PAttr>, msg: impl Into<String>) {
+        self.output.err(CompilerMsg::new(msg.into(), attr.origin));
+    }
+}
+
+impl Display for AttrTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Fn => "functions",
+            Self::Struct => "structs",
+            Self::Field => "fields",
+            Self::Module => "modules",
+            Self::Statement => "statements",
+        })
+    }
+}
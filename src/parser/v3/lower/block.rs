@@ -1,9 +1,9 @@
 use crate::{
-    ir::{IdentID, Type, UIdent, UInstruction, UVar},
+    ir::{IdentID, MemberID, Type, UInstruction},
     parser::{PConstStatement, PStatementLike},
 };
 
-use super::{FnLowerCtx, FnLowerable, Import, PBlock, PStatement};
+use super::{AttrTarget, FnLowerCtx, FnLowerable, PBlock, PStatement};
 
 impl FnLowerable for PBlock {
     type Output = IdentID;
@@ -13,58 +13,84 @@ impl FnLowerable for PBlock {
         let mut statements = Vec::new();
         let mut fn_nodes = Vec::new();
         let mut struct_nodes = Vec::new();
+        let mut alias_nodes = Vec::new();
+        let mut trait_nodes = Vec::new();
+        let mut impl_nodes = Vec::new();
         let mut import_nodes = Vec::new();
+        let mut mod_nodes = Vec::new();
         // first sort statements
         for s in &self.statements {
             let Some(s) = s.as_ref() else {
                 continue;
             };
             match s {
-                PStatementLike::Statement(s) => statements.push(s),
-                PStatementLike::Const(pconst_statement) => match pconst_statement {
-                    PConstStatement::Fn(f) => fn_nodes.push(f),
-                    PConstStatement::Struct(s) => struct_nodes.push(s),
-                    PConstStatement::Import(i) => import_nodes.push(i),
+                PStatementLike::Statement(attrs, s) => statements.push((attrs, s)),
+                PStatementLike::Const(vis, pconst_statement) => match pconst_statement {
+                    PConstStatement::Fn(f) => fn_nodes.push((f, *vis)),
+                    PConstStatement::Struct(s) => struct_nodes.push((s, *vis)),
+                    PConstStatement::Type(t) => alias_nodes.push((t, *vis)),
+                    PConstStatement::Trait(t) => trait_nodes.push((t, *vis)),
+                    PConstStatement::Impl(i) => impl_nodes.push(i),
+                    PConstStatement::Import(i) => import_nodes.push((i, *vis)),
+                    PConstStatement::Mod(m) => mod_nodes.push((m, *vis)),
                 },
             }
         }
-        // then lower imports
-        for i_n in &import_nodes {
-            if let Some(i) = i_n.as_ref() {
-                let name = &i.0;
-                let path = ctx.path_for(name);
-                let import = Import(path.clone());
-                if ctx.imports.insert(import) {
-                    ctx.def_var(UVar {
-                        name: name.clone(),
-                        ty: Type::Module(path),
-                        origin: i_n.origin,
-                    });
-                }
-            }
+        // then lower imports and modules
+        for (i, vis) in &import_nodes {
+            i.lower(ctx.ctx, *vis);
+        }
+        for (m, vis) in &mod_nodes {
+            m.lower(ctx.ctx, *vis);
         }
         // then lower const things
         let mut structs = Vec::new();
-        for s in &struct_nodes {
-            structs.push(s.lower(ctx.ctx));
+        for (s, vis) in &struct_nodes {
+            let id = s.lower(ctx.ctx);
+            if let Some(id) = id {
+                let name = ctx.structs[id].name.clone();
+                ctx.def_member(name, MemberID::Struct(id), *vis);
+            }
+            structs.push(id);
         }
-        for (s, id) in struct_nodes.iter().zip(structs) {
+        for ((s, _), id) in struct_nodes.iter().zip(structs) {
             if let Some(id) = id {
                 s.lower(ctx.ctx);
             }
         }
+        for (t, vis) in &alias_nodes {
+            t.lower(ctx.ctx, *vis);
+        }
+        for (t, vis) in &trait_nodes {
+            t.lower(ctx.ctx, *vis);
+        }
         let mut fns = Vec::new();
-        for f in &fn_nodes {
-            fns.push(f.lower(ctx.ctx));
+        for (f, vis) in &fn_nodes {
+            let id = f.lower(ctx.ctx);
+            if let Some(id) = id {
+                let name = ctx.fns[id].name.clone();
+                ctx.def_member(name, MemberID::Fn(id), *vis);
+            }
+            fns.push(id);
         }
-        for (f, id) in fn_nodes.iter().zip(fns) {
+        for ((f, _), id) in fn_nodes.iter().zip(fns) {
             if let Some(id) = id {
                 f.lower(ctx.ctx);
             }
         }
+        for i in &impl_nodes {
+            i.lower(ctx.ctx);
+        }
         // then lower statements
-        for s in statements {
+        for (attrs, s) in statements {
+            let attrs = ctx.lower_attrs(attrs, AttrTarget::Statement);
+            if !attrs.enabled {
+                last = None;
+                continue;
+            }
+            let outer = std::mem::replace(&mut ctx.lints, attrs.lints);
             last = s.lower(ctx);
+            ctx.lints = outer;
         }
         ctx.ident_stack.pop();
         last
@@ -77,8 +103,7 @@ impl FnLowerable for PStatement {
         match self {
             PStatement::Let(def, e) => {
                 let def = def.lower(ctx.ctx)?;
-                let res = e.lower(ctx);
-                if let Some(res) = res {
+                if let Some(res) = e.as_ref().and_then(|e| e.lower(ctx)) {
                     ctx.push(UInstruction::Mv { dst: def, src: res });
                 }
                 None
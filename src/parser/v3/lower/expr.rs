@@ -1,28 +1,41 @@
-use super::{func::FnLowerCtx, FnLowerable, PExpr, PostfixOp};
+use super::{func::FnLowerCtx, CompilerMsg, FnLowerable, PExpr, PostfixOp};
 use crate::{
-    ir::{IdentID, IdentStatus, MemRes, Member, MemberID, MemberIdent, Type, UData, UInstruction},
-    parser::InfixOp,
+    common::Lint,
+    compiler::arch::riscv::{op32i, t0},
+    ir::{
+        arch::riscv64::{RV64Instruction, RegRef},
+        BinOp, IdentID, IntTy, Len, MemberIdent, Type, UData, UInstruction,
+    },
+    parser::{InfixOp, PIndex},
 };
 
 impl FnLowerable for PExpr {
     type Output = IdentID;
     fn lower(&self, ctx: &mut FnLowerCtx) -> Option<IdentID> {
         let mut e = self;
+        // outermost member first, which is the order resolution pops them in
         let mut path = Vec::new();
         let mut gargs = None;
         loop {
             match e {
                 PExpr::Member(node, ty, ident) => {
-                    e = if let Some(t) = node.as_ref() {
-                        ctx.origin = node.origin;
-                        path.push((ty, ident, gargs.unwrap_or_default()));
-                        &**t
-                    } else {
+                    let Some(t) = node.as_ref() else {
                         return None;
                     };
+                    ctx.origin = node.origin;
+                    path.push(MemberIdent {
+                        ty: *ty,
+                        name: ident.as_ref()?.0.clone(),
+                        origin: ident.origin,
+                        gargs: gargs.take().unwrap_or_default(),
+                    });
+                    e = &**t;
                 }
                 PExpr::Generic(node, nodes) => match gargs {
-                    None => gargs = Some(nodes.iter().map(|t| t.lower(ctx)).collect::<Vec<_>>()),
+                    None => {
+                        gargs = Some(nodes.iter().map(|t| t.lower(ctx)).collect::<Vec<_>>());
+                        e = &**node.as_ref()?;
+                    }
                     Some(_) => {
                         // this should cover the more specific area of ::<...>
                         // but too lazy rn
@@ -33,12 +46,9 @@ impl FnLowerable for PExpr {
                 _ => break,
             }
         }
-        while let PExpr::Member(node, ty, ident) = e {}
-        if path.len() > 0 {
-            // UIdent {
-            //     origin: ctx.origin,
-            //     status: IdentStatus::Unres { base: (), path: () },
-            // }
+        if !path.is_empty() || gargs.is_some() {
+            let base = e.lower(ctx)?;
+            return ctx.extend_ident(base, path, gargs);
         }
         let origin = ctx.origin;
         Some(match e {
@@ -50,32 +60,86 @@ impl FnLowerable for PExpr {
                     let dty = Type::Bits(8).arr(ctx.ctx.p, data.len() as u32);
                     let dty = ctx.def_ty(dty);
                     let src = ctx.def_data(UData {
-                        name: format!("string \"{}\"", s.replace("\n", "\\n")),
+                        name: format!("string {s:?}"),
                         ty: dty,
                         content: data,
                     });
                     ctx.push(UInstruction::LoadSlice { dst, src });
                     dst
                 }
+                super::PLiteral::Bytes(b) => {
+                    let ty = Type::Bits(8).arr(ctx.ctx.p, b.len() as u32);
+                    let ty = ctx.def_ty(ty);
+                    let dst = ctx.temp_var(origin, ty);
+                    let src = ctx.def_data(UData {
+                        name: format!("bytes b\"{}\"", b.escape_ascii()),
+                        ty,
+                        content: b.clone(),
+                    });
+                    ctx.push(UInstruction::LoadData { dst, src });
+                    dst
+                }
                 super::PLiteral::Char(c) => {
-                    let ty = ctx.def_ty(Type::Bits(8));
-                    let dst = ctx.temp_var(origin, ty.clone());
+                    let ty = ctx.def_ty(Type::Char);
+                    let dst = ctx.temp_var(origin, ty);
                     let src = ctx.def_data(UData {
-                        name: format!("char '{c}'"),
+                        name: format!("char {c:?}"),
                         ty,
-                        content: c.to_string().as_bytes().to_vec(),
+                        content: (*c as u32).to_le_bytes().to_vec(),
                     });
                     ctx.push(UInstruction::LoadData { dst, src });
                     dst
                 }
                 super::PLiteral::Number(n) => {
-                    // TODO: temp
-                    let ty = ctx.def_ty(Type::Bits(64));
+                    // a suffix like `5i32` picks the type; without one it's raw bits
+                    let int = n.ty.as_ref().and_then(|s| {
+                        let int = IntTy::from_name(s);
+                        if int.is_none() {
+                            ctx.err(format!("Unknown number suffix '{s}'"));
+                        }
+                        int
+                    });
+                    let (ty, bits, fits_in) = match int {
+                        Some(int) => (Type::Int(int), int.bits, format!("'{}'", int.name())),
+                        None => (Type::Bits(64), 64, "64 bits".to_string()),
+                    };
+                    let ty = ctx.def_ty(ty);
                     let dst = ctx.temp_var(origin, ty.clone());
+                    let parsed = n.whole.parse::<u64>();
+                    let fits = match (&parsed, int) {
+                        (Ok(val), Some(int)) => int.fits(*val),
+                        (parsed, _) => parsed.is_ok(),
+                    };
+                    if !fits {
+                        ctx.lint(
+                            Lint::TruncatingLiteral,
+                            CompilerMsg::new(
+                                format!("Literal {} doesn't fit in {fits_in}", n.whole),
+                                origin,
+                            ),
+                        );
+                    }
+                    // keep the low bits
+                    let val = parsed.unwrap_or_else(|_| {
+                        n.whole.bytes().fold(0u64, |val, d| {
+                            val.wrapping_mul(10).wrapping_add((d - b'0') as u64)
+                        })
+                    });
                     let src = ctx.def_data(UData {
                         name: format!("num {n:?}"),
                         ty,
-                        content: n.whole.parse::<i64>().unwrap().to_le_bytes().to_vec(),
+                        content: val.to_le_bytes()[..bits as usize / 8].to_vec(),
+                    });
+                    ctx.push(UInstruction::LoadData { dst, src });
+                    dst
+                }
+                super::PLiteral::Bool(b) => {
+                    let ty = ctx.def_ty(Type::Bool);
+                    let dst = ctx.temp_var(origin, ty);
+                    let src = ctx.def_data(UData {
+                        name: format!("bool {b}"),
+                        ty,
+                        content: vec![*b as u8],
                     });
                     ctx.push(UInstruction::LoadData { dst, src });
                     dst
@@ -84,12 +148,35 @@ impl FnLowerable for PExpr {
             },
             PExpr::Ident(i) => ctx.ident(i),
             PExpr::BinaryOp(op, e1, e2) => match op {
-                InfixOp::Add => todo!(),
-                InfixOp::Sub => todo!(),
-                InfixOp::Mul => todo!(),
-                InfixOp::Div => todo!(),
-                InfixOp::LessThan => todo!(),
-                InfixOp::GreaterThan => todo!(),
+                InfixOp::Add
+                | InfixOp::Sub
+                | InfixOp::Mul
+                | InfixOp::Div
+                | InfixOp::LessThan
+                | InfixOp::GreaterThan
+                | InfixOp::LessEqual
+                | InfixOp::GreaterEqual
+                | InfixOp::Equal
+                | InfixOp::NotEqual => {
+                    let lhs = e1.lower(ctx)?;
+                    let rhs = e2.lower(ctx)?;
+                    let op = match op {
+                        InfixOp::Add => BinOp::Add,
+                        InfixOp::Sub => BinOp::Sub,
+                        InfixOp::Mul => BinOp::Mul,
+                        InfixOp::Div => BinOp::Div,
+                        InfixOp::LessThan => BinOp::Lt,
+                        InfixOp::GreaterThan => BinOp::Gt,
+                        InfixOp::LessEqual => BinOp::Le,
+                        InfixOp::GreaterEqual => BinOp::Ge,
+                        InfixOp::Equal => BinOp::Eq,
+                        _ => BinOp::Ne,
+                    };
+                    let ty = if op.is_cmp() { Type::Bool } else { Type::Infer };
+                    let dst = ctx.temp(ty);
+                    ctx.push(UInstruction::BinOp { dst, op, lhs, rhs });
+                    dst
+                }
                 InfixOp::Assign => {
                     let res1 = e1.lower(ctx)?;
                     let res2 = e2.lower(ctx)?;
@@ -165,6 +252,76 @@ impl FnLowerable for PExpr {
                 ctx.push(UInstruction::Loop { body });
                 return None;
             }
+            PExpr::While(cond, body) => {
+                // loop { if cond { body; continue }; break }
+                let mut loop_ctx = ctx.branch();
+                let cond = cond.lower(&mut loop_ctx)?;
+                loop_ctx.ident_stack.push();
+                let mut body_ctx = loop_ctx.branch();
+                body.lower(&mut body_ctx);
+                body_ctx.push(UInstruction::Continue);
+                let body = body_ctx.instructions;
+                loop_ctx.ident_stack.pop();
+                loop_ctx.push(UInstruction::If { cond, body });
+                loop_ctx.push(UInstruction::Break);
+                let body = loop_ctx.instructions;
+                ctx.push(UInstruction::Loop { body });
+                return None;
+            }
+            PExpr::For(item, iter, body) => {
+                let name = item.as_ref()?.0.clone();
+                let src = iter.lower(ctx)?;
+                let idx = ctx.const_u64(0);
+                let len = ctx.temp(Type::Bits(64));
+                ctx.push(UInstruction::Len { dst: len, src });
+                let oob = ctx.oob_msg(origin);
+                ctx.ident_stack.push();
+                // same shape as a while loop on `idx < len`
+                let mut loop_ctx = ctx.branch();
+                let more = loop_ctx.temp(Type::Bool);
+                loop_ctx.push(UInstruction::BinOp {
+                    dst: more,
+                    op: BinOp::Lt,
+                    lhs: idx,
+                    rhs: len,
+                });
+                let mut body_ctx = loop_ctx.branch();
+                let ty = Type::Ref(body_ctx.ctx.infer());
+                let rf = body_ctx.temp(ty);
+                body_ctx.push(UInstruction::Index {
+                    dst: rf,
+                    src,
+                    idx,
+                    oob,
+                });
+                let ty = Type::Deref(body_ctx.ctx.infer());
+                let x = body_ctx.named_var(name.clone(), item.origin, ty);
+                body_ctx.push(UInstruction::Deref { dst: x, src: rf });
+                body_ctx.ident_stack.insert(name, x);
+                // step before the body so `continue` doesn't skip it
+                let next = body_ctx.asm_op(
+                    &[idx],
+                    vec![RV64Instruction::OpImm {
+                        op: op32i::ADD,
+                        dest: RegRef::Reg(t0),
+                        src: RegRef::Reg(t0),
+                        imm: 1,
+                    }],
+                );
+                body_ctx.push(UInstruction::Mv {
+                    dst: idx,
+                    src: next,
+                });
+                body.lower(&mut body_ctx);
+                body_ctx.push(UInstruction::Continue);
+                let body = body_ctx.instructions;
+                loop_ctx.push(UInstruction::If { cond: more, body });
+                loop_ctx.push(UInstruction::Break);
+                let body = loop_ctx.instructions;
+                ctx.ident_stack.pop();
+                ctx.push(UInstruction::Loop { body });
+                return None;
+            }
             PExpr::Break => {
                 ctx.push(UInstruction::Break);
                 return None;
@@ -173,34 +330,85 @@ impl FnLowerable for PExpr {
                 ctx.push(UInstruction::Continue);
                 return None;
             }
-            PExpr::Member(e, ty, name) => {
-                let id = e.lower(ctx)?;
-                let name_str = name.as_ref()?.0;
-                let cur = &mut ctx.p.idents[id];
-                match cur.status {
-                    IdentStatus::Res(res) => {
-                        cur.status = IdentStatus::Unres {
-                            base: MemRes {
-                                mem: Member {
-                                    id: MemberID
-                                },
-                                origin: (),
-                                gargs: (),
-                            },
-                            path: (),
-                        }
+            PExpr::Array(elems) => {
+                let mut vars = Vec::new();
+                for e in elems {
+                    vars.push(e.lower(ctx)?);
+                }
+                let ty = Type::Array(ctx.ctx.infer(), vars.len() as Len);
+                let dst = ctx.temp(ty);
+                ctx.push(UInstruction::Array { dst, elems: vars });
+                dst
+            }
+            PExpr::ArrayRepeat(e, len) => {
+                let src = e.lower(ctx)?;
+                let ty = Type::Array(ctx.ctx.infer(), *len);
+                let dst = ctx.temp(ty);
+                ctx.push(UInstruction::Array {
+                    dst,
+                    elems: vec![src; *len as usize],
+                });
+                dst
+            }
+            PExpr::Index(e, index) => {
+                let src = e.lower(ctx)?;
+                let oob = ctx.oob_msg(origin);
+                match index {
+                    PIndex::Single(i) => {
+                        let idx = i.lower(ctx)?;
+                        let ty = Type::Ref(ctx.ctx.infer());
+                        let rf = ctx.temp(ty);
+                        ctx.push(UInstruction::Index {
+                            dst: rf,
+                            src,
+                            idx,
+                            oob,
+                        });
+                        let ty = Type::Deref(ctx.ctx.infer());
+                        let dst = ctx.temp(ty);
+                        ctx.push(UInstruction::Deref { dst, src: rf });
+                        dst
+                    }
+                    PIndex::Range(start, end) => {
+                        let start = match start {
+                            Some(s) => Some(s.lower(ctx)?),
+                            None => None,
+                        };
+                        let end = match end {
+                            Some(e) => Some(e.lower(ctx)?),
+                            None => None,
+                        };
+                        let ty = Type::Slice(ctx.ctx.infer());
+                        let dst = ctx.temp(ty);
+                        ctx.push(UInstruction::SliceRange {
+                            dst,
+                            src,
+                            start,
+                            end,
+                            oob,
+                        });
+                        dst
                     }
-                    IdentStatus::Unres { base, path } => path.push(MemberIdent {
-                        ty: *ty,
-                        name: name_str,
-                        origin: name.origin,
-                        gargs: Vec::new(),
-                    }),
-                    IdentStatus::Failed(res_err) => return None,
-                    IdentStatus::Cooked => return None,
                 }
-                return None;
             }
+            PExpr::SizeOf(ty) | PExpr::AlignOf(ty) => {
+                let ty = ty.lower(ctx);
+                let dty = ctx.def_ty(Type::Bits(64));
+                let dst = ctx.temp_var(origin, dty);
+                ctx.push(match e {
+                    PExpr::SizeOf(_) => UInstruction::SizeOf { dst, ty },
+                    _ => UInstruction::AlignOf { dst, ty },
+                });
+                dst
+            }
+            PExpr::Cast(e, ty) => {
+                let src = e.lower(ctx)?;
+                let ty = ty.lower(ctx);
+                let dst = ctx.temp_var(origin, ty);
+                ctx.push(UInstruction::Cast { dst, src });
+                dst
+            }
+            PExpr::Member(..) | PExpr::Generic(..) => unreachable!("lowered as a path above"),
         })
     }
 }
@@ -1,10 +1,11 @@
 use std::ops::{Deref, DerefMut};
 
-use super::{CompilerMsg, FileSpan, ModuleLowerCtx, Node, PFunction, Typable};
+use super::{AttrTarget, CompilerMsg, FileSpan, ModuleLowerCtx, Node, PFunction, Typable};
 use crate::{
     ir::{
-        FnID, IdentID, IdentStatus, MemRes, Member, MemberID, MemberIdent, MemberPath, MemberTy,
-        Origin, Res, Type, UFunc, UIdent, UInstrInst, UInstruction,
+        DataID, FnID, IdentID, IdentStatus, Len, MemRes, Member, MemberID, MemberIdent, MemberPath,
+        MemberTy, Origin, Res, ResBase, Type, TypeID, UData, UFunc, UIdent, UInstrInst,
+        UInstruction, Visibility,
     },
     parser,
 };
@@ -18,6 +19,10 @@ impl Node<PFunction> {
 impl PFunction {
     pub fn lower(&self, ctx: &mut ModuleLowerCtx, origin: Origin) -> Option<FnID> {
         let header = self.header.as_ref()?;
+        let attrs = ctx.lower_attrs(&self.attrs, AttrTarget::Fn);
+        if !attrs.enabled {
+            return None;
+        }
         let name = header.name.as_ref()?.0.clone();
         let (generics, args, ret) = if let Some(header) = self.header.as_ref() {
             (
@@ -53,6 +58,8 @@ impl PFunction {
             })
             .collect::<Vec<_>>();
         ctx.ident_stack.extend(generics.into_iter());
+        let lints = attrs.lints;
+        let outer = std::mem::replace(&mut ctx.lints, lints.clone());
         let instructions = {
             let mut fctx = FnLowerCtx {
                 instructions: Vec::new(),
@@ -70,6 +77,7 @@ impl PFunction {
             }
             instructions
         };
+        ctx.lints = outer;
         let f = UFunc {
             origin,
             gargs,
@@ -77,6 +85,9 @@ impl PFunction {
             args,
             ret,
             instructions,
+            module: ctx.module,
+            lints,
+            attrs: attrs.attrs,
         };
         Some(ctx.def_fn(f))
     }
@@ -105,10 +116,12 @@ impl<'a, 'b> FnLowerCtx<'a, 'b> {
                         base: MemRes {
                             mem: Member {
                                 id: MemberID::Module(self.module),
+                                vis: Visibility::Public,
                             },
                             origin: self.origin,
                             gargs: Vec::new(),
                         },
+                        scope: self.module,
                     }
                 }
             } else {
@@ -118,6 +131,46 @@ impl<'a, 'b> FnLowerCtx<'a, 'b> {
         };
         self.def_ident(inst)
     }
+    /// `path` is resolved starting from `base`, with its last member first;
+    /// `gargs` go to `base` itself
+    pub fn extend_ident(
+        &mut self,
+        base: IdentID,
+        mut path: Vec<MemberIdent>,
+        gargs: Option<Vec<TypeID>>,
+    ) -> Option<IdentID> {
+        let status = match &self.p.idents[base].status {
+            IdentStatus::Unres {
+                base,
+                path: bpath,
+                scope,
+            } => {
+                let mut bpath = bpath.clone();
+                if let Some(gargs) = gargs {
+                    bpath.first_mut()?.gargs = gargs;
+                }
+                path.extend(bpath);
+                Some(IdentStatus::Unres {
+                    base: base.clone(),
+                    path,
+                    scope: *scope,
+                })
+            }
+            IdentStatus::Res(res) if gargs.is_none() => Some(IdentStatus::Unres {
+                base: ResBase::Validated(res.clone()),
+                path,
+                scope: self.module,
+            }),
+            IdentStatus::Res(_) => None,
+            _ => return None,
+        };
+        let Some(status) = status else {
+            self.err("Cannot specify generics here".to_string());
+            return None;
+        };
+        let origin = self.origin;
+        Some(self.def_ident(UIdent { status, origin }))
+    }
     pub fn err(&mut self, msg: String) {
         let origin = self.origin;
         self.output.err(CompilerMsg::new(msg, origin))
@@ -134,6 +187,36 @@ impl<'a, 'b> FnLowerCtx<'a, 'b> {
     pub fn push_at(&mut self, i: UInstruction, span: FileSpan) {
         self.instructions.push(UInstrInst { i, origin: span });
     }
+    /// what a failed bounds check at `origin` prints before aborting
+    pub fn oob_msg(&mut self, origin: Origin) -> DataID {
+        let path = self
+            .output
+            .file_map
+            .get(&origin.file)
+            .map(|f| f.path.display().to_string())
+            .unwrap_or_default();
+        let loc = format!("{path}:{}:{}", origin.start.line + 1, origin.start.col + 1);
+        let content = format!("index out of bounds at {loc}\n").into_bytes();
+        let ty = Type::Bits(8).arr(self.ctx.p, content.len() as Len);
+        let ty = self.def_ty(ty);
+        self.def_data(UData {
+            name: format!("bounds check {loc}"),
+            ty,
+            content,
+        })
+    }
+    /// loads a 64 bit constant into a new temp
+    pub fn const_u64(&mut self, val: u64) -> IdentID {
+        let ty = self.def_ty(Type::Bits(64));
+        let dst = self.temp(ty);
+        let src = self.def_data(UData {
+            name: format!("num {val}"),
+            ty,
+            content: val.to_le_bytes().to_vec(),
+        });
+        self.push(UInstruction::LoadData { dst, src });
+        dst
+    }
     pub fn branch<'c>(&'c mut self) -> FnLowerCtx<'c, 'b> {
         FnLowerCtx {
             ctx: self.ctx,
@@ -1,11 +1,14 @@
 mod arch;
 mod asm;
+mod attr;
 mod block;
 mod def;
 mod expr;
 mod func;
 mod map;
+mod module;
 mod struc;
+mod trai;
 mod ty;
 
 use std::{
@@ -15,87 +18,134 @@ use std::{
 
 use super::*;
 use crate::{
+    common::{Lint, LintLevels},
     ir::{
-        IdentID, IdentStatus, ModID, Origin, Res, Type, TypeID, UFunc, UIdent, UModule, UProgram,
-        UVar,
+        IdentID, IdentStatus, Member, MemberID, ModID, Origin, Res, Type, TypeID, UIdent,
+        UProgram, UVar, Visibility,
     },
     util::NameStack,
 };
+use attr::AttrTarget;
 pub use func::{FnLowerCtx, FnLowerable};
 
 impl PModule {
     pub fn lower(
         &self,
-        path: Vec<String>,
+        name: String,
+        parent: Option<ModID>,
         p: &mut UProgram,
         imports: &mut Imports,
         output: &mut CompilerOutput,
     ) -> ModID {
-        let name = path.last().unwrap().clone();
-        let f = UFunc {
-            name: name.clone(),
-            args: Vec::new(),
-            instructions: Vec::new(),
-            gargs: Vec::new(),
-            ret: p.def_ty(Type::Unit),
-            origin: self.block.origin,
-        };
-        let fid = p.def_fn(f);
-        let mid = p.def_module(UModule {
-            name,
-            members: HashMap::new(),
-            parent: None,
-            func: fid,
-        });
-        let mut ctx = ModuleLowerCtx {
-            p,
-            output,
-            module: mid,
-            temp: 0,
-            ident_stack: NameStack::new(),
-        };
-        let mut fctx = FnLowerCtx {
-            ctx: &mut ctx,
-            instructions: Vec::new(),
-            origin: self.block.origin,
-        };
-        self.block.lower(&mut fctx);
-        p.fns[fid].instructions = fctx.instructions;
-        mid
+        let lints = LintLevels::default();
+        lower_module(&self.block, name, parent, lints, p, imports, output)
+    }
+}
+
+/// lowers the block of a file or `mod name { ... }` into a new module
+pub fn lower_module(
+    block: &Node<PBlock>,
+    name: String,
+    parent: Option<ModID>,
+    lints: LintLevels,
+    p: &mut UProgram,
+    imports: &mut Imports,
+    output: &mut CompilerOutput,
+) -> ModID {
+    let mid = p.new_module(name, parent, block.origin);
+    // only `main` gets run, so anything else at the top level would silently do nothing
+    if let Some(b) = block.as_ref()
+        && let Some(s) = b
+            .statements
+            .iter()
+            .find(|s| matches!(s.as_ref(), Some(PStatementLike::Statement(_))))
+    {
+        output.err(CompilerMsg::new(
+            "Statements outside of functions are never run; move them into main".to_string(),
+            s.origin,
+        ));
     }
+    let fid = p.modules[mid].func;
+    p.fns[fid].lints = lints.clone();
+    let mut ctx = ModuleLowerCtx {
+        p,
+        output,
+        imports,
+        module: mid,
+        temp: 0,
+        ident_stack: NameStack::new(),
+        lints,
+    };
+    let mut fctx = FnLowerCtx {
+        ctx: &mut ctx,
+        instructions: Vec::new(),
+        origin: block.origin,
+    };
+    block.lower(&mut fctx);
+    let instructions = fctx.instructions;
+    ctx.p.fns[fid].instructions = instructions;
+    mid
 }
 
 pub struct ModuleLowerCtx<'a> {
     pub p: &'a mut UProgram,
     pub output: &'a mut CompilerOutput,
+    pub imports: &'a mut Imports,
     pub module: ModID,
     pub temp: usize,
     pub ident_stack: NameStack<IdentID>,
+    /// lint levels of the item being lowered
+    pub lints: LintLevels,
 }
 
 impl<'a> ModuleLowerCtx<'a> {
-    pub fn new(program: &'a mut UProgram, output: &'a mut CompilerOutput, id: ModID) -> Self {
+    pub fn new(
+        program: &'a mut UProgram,
+        output: &'a mut CompilerOutput,
+        imports: &'a mut Imports,
+        id: ModID,
+    ) -> Self {
         Self {
             p: program,
             output,
+            imports,
             module: id,
             temp: 0,
             ident_stack: NameStack::new(),
+            lints: LintLevels::default(),
         }
     }
+    pub fn def_member(&mut self, name: String, id: MemberID, vis: PVisibility) {
+        let module = self.module;
+        self.p.modules[module].members.insert(
+            name,
+            Member {
+                id,
+                vis: vis.lower(),
+            },
+        );
+    }
+    pub fn lint(&mut self, lint: Lint, msg: CompilerMsg) {
+        self.output.lint(lint, &self.lints, msg);
+    }
     pub fn temp_var(&mut self, origin: Origin, ty: impl Typable) -> IdentID {
         self.temp_var_inner(origin, ty)
     }
     fn temp_var_inner(&mut self, origin: Origin, ty: impl Typable) -> IdentID {
+        let name = format!("temp{}", self.temp);
+        self.temp += 1;
+        self.named_var(name, origin, ty)
+    }
+    /// a var that doesn't come from a `let`, but should still show up by name
+    pub fn named_var(&mut self, name: String, origin: Origin, ty: impl Typable) -> IdentID {
         let var = UVar {
-            name: format!("temp{}", self.temp),
+            name,
             ty: ty.ty(self),
             origin,
             parent: None,
             children: HashMap::new(),
         };
         let id = self.p.def_var(var);
-        self.temp += 1;
         self.def_ident(UIdent {
             status: IdentStatus::Res(Res::Var(id)),
             origin,
@@ -103,6 +153,15 @@ impl<'a> ModuleLowerCtx<'a> {
     }
 }
 
+impl PVisibility {
+    pub fn lower(&self) -> Visibility {
+        match self {
+            PVisibility::Public => Visibility::Public,
+            PVisibility::Private => Visibility::Private,
+        }
+    }
+}
+
 pub trait Typable {
     fn ty(self, p: &mut UProgram) -> TypeID;
 }
@@ -0,0 +1,133 @@
+use crate::{
+    ir::{MemberID, MemberIdent, MemberTy, ModID, UseDef},
+    parser::{Import, PIdent, PModDef, PUseKind, PUseTree, PVisibility},
+};
+
+use super::{lower_module, AttrTarget, CompilerMsg, ModuleLowerCtx, Node};
+
+impl Node<PModDef> {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx, vis: PVisibility) -> Option<ModID> {
+        let def = self.as_ref()?;
+        let attrs = ctx.lower_attrs(&def.attrs, AttrTarget::Module);
+        if !attrs.enabled {
+            return None;
+        }
+        let name = def.name.as_ref()?.to_string();
+        let id = lower_module(
+            &def.body,
+            name.clone(),
+            Some(ctx.module),
+            attrs.lints,
+            ctx.p,
+            ctx.imports,
+            ctx.output,
+        );
+        ctx.def_member(name, MemberID::Module(id), vis);
+        Some(id)
+    }
+}
+
+impl Node<PUseTree> {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx, vis: PVisibility) {
+        let Some(tree) = self.as_ref() else {
+            return;
+        };
+        let mut leaves = Vec::new();
+        tree.leaves(&mut Vec::new(), &mut leaves);
+        for (path, alias) in leaves {
+            lower_use(ctx, &path, alias, vis);
+        }
+    }
+}
+
+type UseLeaf<'a> = (Vec<&'a Node<PIdent>>, Option<&'a Node<PIdent>>);
+
+impl PUseTree {
+    /// flattens `a::{b, c::d}` into `a::b` and `a::c::d`
+    fn leaves<'a>(&'a self, prefix: &mut Vec<&'a Node<PIdent>>, out: &mut Vec<UseLeaf<'a>>) {
+        let len = prefix.len();
+        prefix.extend(&self.path);
+        match &self.kind {
+            PUseKind::Single(alias) => out.push((prefix.clone(), alias.as_ref())),
+            PUseKind::List(list) => {
+                for tree in list.iter().flat_map(|t| t.as_ref()) {
+                    tree.leaves(prefix, out);
+                }
+            }
+        }
+        prefix.truncate(len);
+    }
+}
+
+fn lower_use(
+    ctx: &mut ModuleLowerCtx,
+    path: &[&Node<PIdent>],
+    alias: Option<&Node<PIdent>>,
+    vis: PVisibility,
+) -> Option<()> {
+    let origin = path.first()?.origin;
+    let mut names = Vec::new();
+    for seg in path {
+        names.push((seg.as_ref()?.to_string(), seg.origin));
+    }
+    let mut module = ctx.module;
+    let mut rest = &names[..];
+    if let Some(((first, _), next)) = rest.split_first()
+        && first == "crate"
+    {
+        module = ctx.crate_root(module);
+        rest = next;
+    }
+    while let Some(((first, origin), next)) = rest.split_first()
+        && first == "super"
+    {
+        let Some(parent) = ctx.modules[module].parent else {
+            ctx.output.err(CompilerMsg::new(
+                format!("Module '{}' has no parent", ctx.module_path(module)),
+                *origin,
+            ));
+            return None;
+        };
+        module = parent;
+        rest = next;
+    }
+    if rest.iter().any(|(n, _)| n == "crate" || n == "super") {
+        ctx.output.err(CompilerMsg::new(
+            "`crate` and `super` can only start a path".to_string(),
+            origin,
+        ));
+        return None;
+    }
+    let Some(((last, _), _)) = rest.split_last() else {
+        ctx.output.err(CompilerMsg::new(
+            "Expected a name to import".to_string(),
+            origin,
+        ));
+        return None;
+    };
+    ctx.imports.push(Import {
+        path: rest.iter().map(|(n, _)| n.clone()).collect(),
+        module,
+        origin,
+    });
+    // `import util;` is handled by the loader, which puts `util` in scope
+    if rest.len() == 1 && alias.is_none() && module == ctx.module {
+        return Some(());
+    }
+    let name = match alias {
+        Some(alias) => alias.as_ref()?.to_string(),
+        None => last.clone(),
+    };
+    let path = rest
+        .iter()
+        .rev()
+        .map(|(name, origin)| MemberIdent {
+            ty: MemberTy::Member,
+            name: name.clone(),
+            gargs: Vec::new(),
+            origin: *origin,
+        })
+        .collect();
+    ctx.def_member(name, MemberID::Use(UseDef { module, path }), vis);
+    Some(())
+}
@@ -1,25 +1,40 @@
+use std::collections::HashSet;
+
 use crate::{
-    common::FileSpan,
-    ir::{StructField, StructID, UStruct},
+    common::{CompilerMsg, FileSpan},
+    ir::{StructField, StructID, UStruct, Visibility},
     parser::{PStruct, PStructFields},
 };
 
-use super::ModuleLowerCtx;
+use super::{AttrTarget, ModuleLowerCtx};
 
 impl PStruct {
     pub fn lower(&self, ctx: &mut ModuleLowerCtx, span: FileSpan) -> Option<StructID> {
+        let attrs = ctx.lower_attrs(&self.attrs, AttrTarget::Struct);
+        if !attrs.enabled {
+            return None;
+        }
+        let outer = std::mem::replace(&mut ctx.lints, attrs.lints.clone());
         ctx.ident_stack.push();
         let gmap: Vec<_> = self.generics.iter().flat_map(|a| a.lower(ctx)).collect();
         let gargs = gmap.iter().map(|(_, id)| *id).collect();
-        let fields = match &self.fields {
+        let mut fields: Vec<(String, StructField)> = match &self.fields {
             PStructFields::Named(nodes) => nodes
                 .iter()
                 .flat_map(|n| {
                     let def = n.as_ref()?;
+                    let attrs = ctx.lower_attrs(&def.attrs, AttrTarget::Field);
+                    if !attrs.enabled {
+                        return None;
+                    }
                     let name = def.name.as_ref()?.to_string();
-                    let tynode = def.ty.as_ref()?;
-                    let ty = tynode.lower(ctx);
-                    Some((name, ty))
+                    let ty = def.ty.lower(ctx);
+                    Some((name, StructField {
+                        ty,
+                        origin: n.origin,
+                        vis: def.vis.lower(),
+                        lints: attrs.lints,
+                    }))
                 })
                 .collect(),
             PStructFields::Tuple(nodes) => nodes
@@ -27,14 +42,29 @@ impl PStruct {
                 .enumerate()
                 .flat_map(|(i, n)| {
                     let ty = n.as_ref()?.lower(ctx, span);
-                    Some((format!("{i}"), ty))
+                    // tuple fields are private for now, there's no syntax for it
+                    Some((format!("{i}"), StructField {
+                        ty,
+                        origin: n.origin,
+                        vis: Visibility::Private,
+                        lints: ctx.lints.clone(),
+                    }))
                 })
                 .collect(),
             PStructFields::None => vec![],
-        }
-        .into_iter()
-        .map(|(name, ty)| (name, StructField { ty }))
-        .collect();
+        };
+        let mut seen = HashSet::new();
+        fields.retain(|(name, field)| {
+            if seen.insert(name.clone()) {
+                return true;
+            }
+            ctx.output.err(CompilerMsg::new(
+                format!("Field '{name}' is already defined"),
+                field.origin,
+            ));
+            false
+        });
+        ctx.lints = outer;
         let name = self.name.as_ref()?.to_string();
         ctx.ident_stack.pop();
         Some(ctx.def_struct(UStruct {
@@ -42,6 +72,10 @@ impl PStruct {
             gargs,
             fields,
             origin: span,
+            module: ctx.module,
+            copy: self.copy,
+            lints: attrs.lints,
+            attrs: attrs.attrs,
         }))
     }
 }
@@ -0,0 +1,97 @@
+use crate::{
+    common::FileSpan,
+    ir::{ImplID, MemberID, TraitID, Type, UImpl, UTrait, UTraitFn},
+    parser::{PImpl, PTrait, PVisibility},
+};
+
+use super::{CompilerMsg, ModuleLowerCtx, Node};
+
+impl Node<PTrait> {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx, vis: PVisibility) -> Option<TraitID> {
+        self.as_ref()?.lower(ctx, self.origin, vis)
+    }
+}
+
+impl Node<PImpl> {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx) -> Option<ImplID> {
+        self.as_ref()?.lower(ctx, self.origin)
+    }
+}
+
+impl PTrait {
+    pub fn lower(
+        &self,
+        ctx: &mut ModuleLowerCtx,
+        origin: FileSpan,
+        vis: PVisibility,
+    ) -> Option<TraitID> {
+        let name = self.name.as_ref()?.to_string();
+        let mut fns = Vec::new();
+        for f in &self.fns {
+            let Some(header) = f.as_ref() else {
+                continue;
+            };
+            let Some(fname) = header.name.as_ref() else {
+                continue;
+            };
+            if !header.gargs.is_empty() {
+                ctx.output.err(CompilerMsg::new(
+                    "Trait functions cannot be generic".to_string(),
+                    f.origin,
+                ));
+            }
+            let args = header
+                .args
+                .iter()
+                .map(|a| match a.as_ref().and_then(|a| a.ty.as_ref()) {
+                    Some(ty) => ty.lower(ctx),
+                    None => {
+                        ctx.output.err(CompilerMsg::new(
+                            "Trait function arguments must have a type".to_string(),
+                            a.origin,
+                        ));
+                        ctx.tc.error
+                    }
+                })
+                .collect::<Vec<_>>();
+            if args.is_empty() {
+                ctx.output.err(CompilerMsg::new(
+                    "Trait functions must take a receiver as their first argument".to_string(),
+                    f.origin,
+                ));
+            }
+            let ret = match &header.ret {
+                Some(ty) => ty.lower(ctx),
+                None => ctx.def_ty(Type::Unit),
+            };
+            fns.push(UTraitFn {
+                name: fname.to_string(),
+                origin: f.origin,
+                args,
+                ret,
+            });
+        }
+        let id = ctx.def_trait(UTrait {
+            name: name.clone(),
+            origin,
+            fns,
+        });
+        ctx.def_member(name, MemberID::Trait(id), vis);
+        Some(id)
+    }
+}
+
+impl PImpl {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx, origin: FileSpan) -> Option<ImplID> {
+        let trait_ = self.trait_.lower_path(ctx)?;
+        let ty = self.for_.lower(ctx);
+        let fns = self.fns.iter().flat_map(|f| f.lower(ctx)).collect();
+        Some(ctx.def_impl(UImpl {
+            trait_,
+            ty,
+            origin,
+            fns,
+            methods: Vec::new(),
+        }))
+    }
+}
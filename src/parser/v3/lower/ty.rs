@@ -1,9 +1,12 @@
 use crate::{
-    ir::{GenericID, MemberIdent, MemberPath, Type, TypeID, UGeneric, UProgram},
-    parser::PGenericDef,
+    ir::{
+        GenericID, IdentID, IdentStatus, IntTy, MemberID, MemberIdent, MemberPath, MemberTy, Res,
+        ResBase, Type, TypeDef, TypeID, UGeneric, UIdent, UProgram,
+    },
+    parser::{PGenericDef, PTypeAlias, PVisibility},
 };
 
-use super::{FileSpan, ModuleLowerCtx, Node, PType};
+use super::{CompilerMsg, FileSpan, ModuleLowerCtx, Node, PType};
 
 impl Node<Box<PType>> {
     pub fn lower(&self, ctx: &mut ModuleLowerCtx) -> TypeID {
@@ -54,6 +57,11 @@ impl PType {
             });
             return ctx.def_ty(ty);
         }
+        if let PType::Ident(node) = ty
+            && let Some(ty) = builtin_type(&node.0)
+        {
+            return ctx.def_ty(ty);
+        }
         let ty = match ty {
             PType::Member(_, _) => unreachable!(),
             PType::Ident(node) => {
@@ -68,12 +76,109 @@ impl PType {
                 })
             }
             PType::Ref(node) => node.lower(ctx).rf(),
+            PType::Array(node, len) => node.lower(ctx).arr(*len),
+            PType::Dyn(node) => match node.as_ref().and_then(|t| t.lower_path(ctx, node.origin)) {
+                Some(id) => Type::Dyn(id),
+                None => return ctx.tc.error,
+            },
             PType::Generic(node, nodes) => todo!(),
         };
         ctx.def_ty(ty)
     }
 }
 
+/// types that are always in scope; these aren't idents so they can't be shadowed
+fn builtin_type(name: &str) -> Option<Type> {
+    match name {
+        "bool" => Some(Type::Bool),
+        "char" => Some(Type::Char),
+        _ => IntTy::from_name(name).map(Type::Int),
+    }
+}
+
+impl Node<PType> {
+    pub fn lower_path(&self, ctx: &mut ModuleLowerCtx) -> Option<IdentID> {
+        self.as_ref()?.lower_path(ctx, self.origin)
+    }
+}
+
+impl PType {
+    /// lowers a plain path (eg. `a::B`) into an ident; used for things that aren't types, like traits
+    pub fn lower_path(&self, ctx: &mut ModuleLowerCtx, origin: FileSpan) -> Option<IdentID> {
+        let mut ty = self;
+        let mut cur_origin = origin;
+        // resolution pops from the end, so this is built innermost name first
+        let mut path = Vec::new();
+        loop {
+            match ty {
+                PType::Member(node, name) => {
+                    path.push(MemberIdent {
+                        ty: MemberTy::Member,
+                        name: name.as_ref()?.0.clone(),
+                        origin: name.origin,
+                        gargs: Vec::new(),
+                    });
+                    cur_origin = node.origin;
+                    ty = node.as_ref()?;
+                }
+                PType::Ident(name) => {
+                    path.push(MemberIdent {
+                        ty: MemberTy::Member,
+                        name: name.0.clone(),
+                        origin: cur_origin,
+                        gargs: Vec::new(),
+                    });
+                    break;
+                }
+                _ => {
+                    ctx.output
+                        .err(CompilerMsg::new("Expected a path".to_string(), cur_origin));
+                    return None;
+                }
+            }
+        }
+        let module = ctx.module;
+        let base = ResBase::Validated(Res::Module(module));
+        Some(ctx.def_ident(UIdent {
+            status: IdentStatus::Unres {
+                base,
+                path,
+                scope: module,
+            },
+            origin,
+        }))
+    }
+}
+
+impl Node<PTypeAlias> {
+    pub fn lower(&self, ctx: &mut ModuleLowerCtx, vis: PVisibility) -> Option<()> {
+        let alias = self.as_ref()?;
+        let name = alias.name.as_ref()?.to_string();
+        ctx.ident_stack.push();
+        let mut gargs = Vec::new();
+        for g in &alias.generics {
+            let Some((gname, id)) = g.lower(ctx) else {
+                continue;
+            };
+            gargs.push(id);
+            let ident = ctx.def_ident(UIdent {
+                status: IdentStatus::Res(Res::Generic(id)),
+                origin: g.origin,
+            });
+            ctx.ident_stack.insert(gname, ident);
+        }
+        let ty = alias.ty.lower(ctx);
+        ctx.ident_stack.pop();
+        let id = MemberID::Type(TypeDef {
+            name: name.clone(),
+            gargs,
+            ty,
+        });
+        ctx.def_member(name, id, vis);
+        Some(())
+    }
+}
+
 impl Node<PGenericDef> {
     pub fn lower(&self, p: &mut UProgram) -> Option<(String, GenericID)> {
         let s = self.as_ref()?;
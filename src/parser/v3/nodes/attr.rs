@@ -0,0 +1,81 @@
+use std::fmt::Debug;
+
+use super::{
+    peek_string, util::parse_list, Node, PIdent, PString, Parsable, ParseResult, ParserCtx,
+    StrKind, Symbol,
+};
+
+/// `#[name]` or `#[name(arg, ...)]`
+pub struct PAttr {
+    pub name: Node<PIdent>,
+    pub args: Vec<Node<PAttrArg>>,
+}
+
+pub enum PAttrArg {
+    Ident(PIdent),
+    Str(String),
+}
+
+impl Parsable for PAttr {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        ctx.expect_sym(Symbol::Hash)?;
+        ctx.expect_sym(Symbol::OpenSquare)?;
+        let name = ctx.parse()?;
+        let args = if ctx.expect_peek()?.is_symbol(Symbol::OpenParen) {
+            ctx.next();
+            parse_list(ctx, Symbol::CloseParen)?
+        } else {
+            Vec::new()
+        };
+        ctx.expect_sym(Symbol::CloseSquare)?;
+        ParseResult::Ok(Self { name, args })
+    }
+}
+
+impl Parsable for PAttrArg {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        if peek_string(ctx) == Some(StrKind::Str) {
+            return ParseResult::Ok(Self::Str(PString::parse(ctx)?.0));
+        }
+        ParseResult::Ok(Self::Ident(PIdent::parse(ctx)?))
+    }
+}
+
+/// parses any attributes coming up
+pub fn parse_attrs(ctx: &mut ParserCtx) -> Vec<Node<PAttr>> {
+    let mut attrs = Vec::new();
+    while ctx.peek().is_some_and(|t| t.is_symbol(Symbol::Hash)) {
+        let res = ctx.parse();
+        attrs.push(res.node);
+        if res.recover {
+            ctx.seek_sym(Symbol::CloseSquare);
+        }
+    }
+    attrs
+}
+
+impl Debug for PAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#[{:?}", self.name)?;
+        if !self.args.is_empty() {
+            f.write_str("(")?;
+            for (i, arg) in self.args.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                arg.fmt(f)?;
+            }
+            f.write_str(")")?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl Debug for PAttrArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(i) => i.fmt(f),
+            Self::Str(s) => s.fmt(f),
+        }
+    }
+}
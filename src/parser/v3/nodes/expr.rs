@@ -1,10 +1,14 @@
 use std::fmt::{Debug, Write};
 
-use crate::{common::FilePos, ir::MemberTy, parser::NodeParsableWith};
+use crate::{
+    common::FilePos,
+    ir::{Len, MemberTy},
+    parser::NodeParsableWith,
+};
 
 use super::{
     op::{InfixOp, PostfixOp},
-    util::parse_list,
+    util::{parse_len, parse_list},
     CompilerMsg, Keyword, Node, PAsmBlock, PBlock, PIdent, PLiteral, PMap, PType, Parsable,
     ParseResult, ParserCtx, Symbol,
 };
@@ -25,8 +29,29 @@ pub enum PExpr {
     Construct(BoxNode, Node<PMap>),
     If(BoxNode, BoxNode),
     Loop(BoxNode),
+    /// `while cond { ... }`
+    While(BoxNode, BoxNode),
+    /// `for x in a { ... }` over an array or slice
+    For(Node<PIdent>, BoxNode, BoxNode),
     Break,
     Continue,
+    /// `[a, b, c]`
+    Array(Vec<Node<PExpr>>),
+    /// `[a; N]`
+    ArrayRepeat(BoxNode, Len),
+    Index(BoxNode, PIndex),
+    /// `size_of<T>`, in bytes
+    SizeOf(Node<PType>),
+    /// `align_of<T>`, in bytes
+    AlignOf(Node<PType>),
+    /// `x as T`
+    Cast(BoxNode, Node<PType>),
+}
+
+pub enum PIndex {
+    Single(BoxNode),
+    /// `a[start..end]`, either side can be left out
+    Range(Option<BoxNode>, Option<BoxNode>),
 }
 
 impl Parsable for PExpr {
@@ -64,6 +89,11 @@ impl PExpr {
                 let args = parse_list(ctx, Symbol::CloseParen)?;
                 e1 = Self::Call(Node::new(e1, span).bx(), args);
                 continue;
+            } else if next.is_symbol(Symbol::OpenSquare) {
+                ctx.next();
+                let index = PIndex::parse(ctx)?;
+                e1 = Self::Index(Node::new(e1, span).bx(), index);
+                continue;
             } else if next.is_symbol(Symbol::OpenCurly) {
                 ctx.next();
                 let map = ctx.parse()?;
@@ -89,6 +119,11 @@ impl PExpr {
                 ctx.next();
                 e1 = Self::PostfixOp(Node::new(e1, span).bx(), op);
                 continue;
+            } else if next.is_keyword(Keyword::As) {
+                ctx.next();
+                let ty = ctx.parse()?;
+                e1 = Self::Cast(Node::new(e1, span).bx(), ty);
+                continue;
             }
             break;
         }
@@ -111,6 +146,28 @@ impl PExpr {
         } else if next.is_symbol(Symbol::OpenCurly) {
             ctx.next();
             Self::Block(PBlock::parse_node(ctx, Some(Symbol::CloseCurly))?)
+        } else if next.is_symbol(Symbol::OpenSquare) {
+            ctx.next();
+            if ctx.expect_peek()?.is_symbol(Symbol::CloseSquare) {
+                ctx.next();
+                return ParseResult::Ok(Self::Array(Vec::new()));
+            }
+            let first: Node<PExpr> = ctx.parse()?;
+            if ctx.expect_peek()?.is_symbol(Symbol::Semicolon) {
+                ctx.next();
+                let len = parse_len(ctx)?;
+                ctx.expect_sym(Symbol::CloseSquare)?;
+                Self::ArrayRepeat(first.bx(), len)
+            } else {
+                let mut elems = vec![first];
+                if ctx.expect_peek()?.is_symbol(Symbol::Comma) {
+                    ctx.next();
+                    elems.extend(parse_list(ctx, Symbol::CloseSquare)?);
+                } else {
+                    ctx.expect_sym(Symbol::CloseSquare)?;
+                }
+                Self::Array(elems)
+            }
         } else if next.is_keyword(Keyword::If) {
             ctx.next();
             let cond = ctx.parse()?.bx();
@@ -120,12 +177,35 @@ impl PExpr {
             ctx.next();
             let body = ctx.parse()?.bx();
             Self::Loop(body)
+        } else if next.is_keyword(Keyword::While) {
+            ctx.next();
+            let cond = ctx.parse()?.bx();
+            let body = ctx.parse()?.bx();
+            Self::While(cond, body)
+        } else if next.is_keyword(Keyword::For) {
+            ctx.next();
+            let item = ctx.parse()?;
+            ctx.expect_kw(Keyword::In)?;
+            let iter = ctx.parse()?.bx();
+            let body = ctx.parse()?.bx();
+            Self::For(item, iter, body)
         } else if next.is_keyword(Keyword::Break) {
             ctx.next();
             Self::Break
         } else if next.is_keyword(Keyword::Continue) {
             ctx.next();
             Self::Continue
+        } else if next.is_keyword(Keyword::SizeOf) || next.is_keyword(Keyword::AlignOf) {
+            let size = next.is_keyword(Keyword::SizeOf);
+            ctx.next();
+            ctx.expect_sym(Symbol::OpenAngle)?;
+            let ty = ctx.parse()?;
+            ctx.expect_sym(Symbol::CloseAngle)?;
+            if size {
+                Self::SizeOf(ty)
+            } else {
+                Self::AlignOf(ty)
+            }
         } else if next.is_keyword(Keyword::Asm) {
             ctx.next();
             Self::AsmBlock(ctx.parse()?)
@@ -143,6 +223,31 @@ impl PExpr {
     }
 }
 
+impl PIndex {
+    /// after the `[`
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let start = if ctx.expect_peek()?.is_symbol(Symbol::DoubleDot) {
+            None
+        } else {
+            Some(ctx.parse()?.bx())
+        };
+        let index = match start {
+            Some(i) if !ctx.expect_peek()?.is_symbol(Symbol::DoubleDot) => Self::Single(i),
+            start => {
+                ctx.expect_sym(Symbol::DoubleDot)?;
+                let end = if ctx.expect_peek()?.is_symbol(Symbol::CloseSquare) {
+                    None
+                } else {
+                    Some(ctx.parse()?.bx())
+                };
+                Self::Range(start, end)
+            }
+        };
+        ctx.expect_sym(Symbol::CloseSquare)?;
+        ParseResult::Ok(index)
+    }
+}
+
 pub fn fix_precedence(
     mut n1: BoxNode,
     mut op: InfixOp,
@@ -197,10 +302,29 @@ impl Debug for PExpr {
             PExpr::Construct(node, inner) => write!(f, "{:?}{:?}", node, inner)?,
             PExpr::If(cond, res) => write!(f, "if {cond:?} then {res:?}")?,
             PExpr::Loop(res) => write!(f, "loop -> {res:?}")?,
+            PExpr::While(cond, res) => write!(f, "while {cond:?} -> {res:?}")?,
+            PExpr::For(item, iter, res) => write!(f, "for {item:?} in {iter:?} -> {res:?}")?,
             PExpr::Break => write!(f, "break")?,
             PExpr::Continue => write!(f, "continue")?,
             PExpr::Member(e1, ty, name) => write!(f, "{:?}{}{:?}", e1, ty.sep(), name)?,
             PExpr::Generic(e1, gargs) => write!(f, "{:?}<{:?}>", e1, gargs)?,
+            PExpr::Array(elems) => write!(f, "{elems:?}")?,
+            PExpr::ArrayRepeat(e, len) => write!(f, "[{e:?}; {len}]")?,
+            PExpr::Index(e, PIndex::Single(i)) => write!(f, "{e:?}[{i:?}]")?,
+            PExpr::Index(e, PIndex::Range(start, end)) => {
+                write!(f, "{e:?}[")?;
+                if let Some(start) = start {
+                    start.fmt(f)?;
+                }
+                f.write_str("..")?;
+                if let Some(end) = end {
+                    end.fmt(f)?;
+                }
+                f.write_char(']')?;
+            }
+            PExpr::SizeOf(ty) => write!(f, "size_of<{ty:?}>")?,
+            PExpr::AlignOf(ty) => write!(f, "align_of<{ty:?}>")?,
+            PExpr::Cast(e, ty) => write!(f, "({e:?} as {ty:?})")?,
         }
         Ok(())
     }
@@ -1,6 +1,6 @@
 use super::{
-    util::parse_list, Node, PBlock, PGenericDef, PIdent, PType, PVarDef, Parsable, ParseResult,
-    ParserCtx, Symbol,
+    util::parse_list, Node, PAttr, PBlock, PGenericDef, PIdent, PType, PVarDef, Parsable,
+    ParseResult, ParserCtx, Symbol,
 };
 use std::fmt::Debug;
 
@@ -14,6 +14,8 @@ pub struct PFunctionHeader {
 pub struct PFunction {
     pub header: Node<PFunctionHeader>,
     pub body: Node<PBlock>,
+    /// set by the statement parser, since they come before `fn`
+    pub attrs: Vec<Node<PAttr>>,
 }
 
 impl Parsable for PFunctionHeader {
@@ -57,7 +59,11 @@ impl Parsable for PFunction {
         let header = ctx.parse()?;
         ctx.expect_sym(Symbol::OpenCurly)?;
         let body = ctx.parse_with(Some(Symbol::CloseCurly))?;
-        ParseResult::Ok(Self { header, body })
+        ParseResult::Ok(Self {
+            header,
+            body,
+            attrs: Vec::new(),
+        })
     }
 }
 
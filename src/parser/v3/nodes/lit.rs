@@ -1,13 +1,19 @@
 use crate::parser::{Parsable, ParseResult};
 
-use super::{PString, ParserCtx, Symbol, Token};
+use super::{
+    escape, peek_string, CompilerMsg, Escaped, Keyword, PByteString, PString, ParserCtx, StrKind,
+    Symbol, Token,
+};
 use std::fmt::Debug;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum PLiteral {
     String(String),
+    /// `b"..."`, an array of bytes
+    Bytes(Vec<u8>),
     Char(char),
     Number(PNumber),
+    Bool(bool),
     Unit,
 }
 
@@ -20,22 +26,43 @@ pub struct PNumber {
 
 impl Parsable for Option<PLiteral> {
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        match peek_string(ctx) {
+            Some(StrKind::Str) => {
+                return ParseResult::Ok(Some(PLiteral::String(PString::parse(ctx)?.0)));
+            }
+            Some(StrKind::Bytes) => {
+                return ParseResult::Ok(Some(PLiteral::Bytes(PByteString::parse(ctx)?.0)));
+            }
+            Some(StrKind::Char) | None => (),
+        }
         let inst = ctx.expect_peek()?;
         ParseResult::Ok(Some(match &inst.token {
             Token::Symbol(Symbol::SingleQuote) => {
-                let chars = ctx.chars();
-                let c = chars.expect_next()?;
-                chars.expect('\'')?;
+                let c = match ctx.chars().expect_next()? {
+                    '\\' => {
+                        let start = ctx.chars().prev_pos();
+                        match escape(ctx, start, StrKind::Char)? {
+                            Escaped::Char(c) => c,
+                            Escaped::Byte(b) => b as char,
+                            Escaped::Skip => unreachable!("chars can't be continued"),
+                        }
+                    }
+                    '\'' => {
+                        let span = ctx.next_start().to(ctx.chars().prev_pos());
+                        ctx.err(CompilerMsg::new("Empty char literal".to_string(), span));
+                        ctx.next();
+                        return ParseResult::Ok(Some(PLiteral::Char('\0')));
+                    }
+                    c => c,
+                };
+                ctx.chars().expect('\'')?;
                 ctx.next();
                 PLiteral::Char(c)
             }
-            Token::Symbol(Symbol::DoubleQuote) => {
+            Token::Keyword(kw @ (Keyword::True | Keyword::False)) => {
+                let b = *kw == Keyword::True;
                 ctx.next();
-                let s = ctx.parse::<PString>()?;
-                return match s.inner {
-                    Some(s) => ParseResult::Ok(Some(PLiteral::String(s.0))),
-                    None => ParseResult::SubErr,
-                };
+                PLiteral::Bool(b)
             }
             Token::Word(text) => {
                 let first = text.chars().next().unwrap();
@@ -90,8 +117,10 @@ impl Debug for PLiteral {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::String(str) => str.fmt(f),
+            Self::Bytes(b) => write!(f, "b\"{}\"", b.escape_ascii()),
             Self::Char(c) => c.fmt(f),
             Self::Number(n) => n.fmt(f),
+            Self::Bool(b) => b.fmt(f),
             Self::Unit => f.write_str("()"),
         }
     }
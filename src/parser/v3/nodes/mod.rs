@@ -1,12 +1,14 @@
 mod asm_block;
 mod asm_fn;
 mod asm_instr;
+mod attr;
 mod block;
 mod def;
 mod expr;
 mod func;
 mod ident;
 mod lit;
+mod module;
 mod op;
 mod statement;
 mod string;
@@ -18,12 +20,14 @@ mod util;
 pub use asm_block::*;
 pub use asm_fn::*;
 pub use asm_instr::*;
+pub use attr::*;
 pub use block::*;
 pub use def::*;
 pub use expr::*;
 pub use func::*;
 pub use ident::*;
 pub use lit::*;
+pub use module::*;
 pub use op::*;
 pub use statement::*;
 pub use struc::*;
@@ -0,0 +1,104 @@
+use std::fmt::Debug;
+
+use super::{
+    util::parse_list, Keyword, Node, PAttr, PBlock, PIdent, Parsable, ParseResult, ParserCtx,
+    Symbol, Token,
+};
+
+/// `mod name { ... }`
+#[derive(Debug)]
+pub struct PModDef {
+    pub name: Node<PIdent>,
+    pub body: Node<PBlock>,
+    pub attrs: Vec<Node<PAttr>>,
+}
+
+/// `a::b::{c, d as e}`; `super` and `crate` are kept as path segments by name
+pub struct PUseTree {
+    pub path: Vec<Node<PIdent>>,
+    pub kind: PUseKind,
+}
+
+pub enum PUseKind {
+    /// the last segment of the path, optionally renamed
+    Single(Option<Node<PIdent>>),
+    List(Vec<Node<PUseTree>>),
+}
+
+impl Parsable for PModDef {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let name = ctx.parse()?;
+        ctx.expect_sym(Symbol::OpenCurly)?;
+        ctx.parse_with(Some(Symbol::CloseCurly))
+            .map_res(|body| Self {
+                name,
+                body,
+                attrs: Vec::new(),
+            })
+    }
+}
+
+impl Parsable for PUseTree {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let mut path = Vec::new();
+        loop {
+            let next = ctx.expect_peek()?;
+            if next.is_symbol(Symbol::OpenCurly) && !path.is_empty() {
+                ctx.next();
+                let list = parse_list(ctx, Symbol::CloseCurly)?;
+                return ParseResult::Ok(Self {
+                    path,
+                    kind: PUseKind::List(list),
+                });
+            }
+            let seg = match next.token {
+                Token::Keyword(Keyword::Super) => Some("super"),
+                Token::Keyword(Keyword::Crate) => Some("crate"),
+                _ => None,
+            };
+            if let Some(seg) = seg {
+                let span = next.span;
+                ctx.next();
+                path.push(Node::new(PIdent(seg.to_string()), span));
+            } else {
+                path.push(ctx.parse()?);
+            }
+            match ctx.peek() {
+                Some(next) if next.is_symbol(Symbol::DoubleColon) => {
+                    ctx.next();
+                }
+                Some(next) if next.is_keyword(Keyword::As) => {
+                    ctx.next();
+                    let alias = ctx.parse()?;
+                    return ParseResult::Ok(Self {
+                        path,
+                        kind: PUseKind::Single(Some(alias)),
+                    });
+                }
+                _ => {
+                    return ParseResult::Ok(Self {
+                        path,
+                        kind: PUseKind::Single(None),
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Debug for PUseTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, seg) in self.path.iter().enumerate() {
+            if i > 0 {
+                f.write_str("::")?;
+            }
+            seg.fmt(f)?;
+        }
+        match &self.kind {
+            PUseKind::Single(None) => (),
+            PUseKind::Single(Some(alias)) => write!(f, " as {alias:?}")?,
+            PUseKind::List(list) => write!(f, "::{list:?}")?,
+        }
+        Ok(())
+    }
+}
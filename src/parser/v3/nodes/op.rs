@@ -8,6 +8,10 @@ pub enum InfixOp {
     Div,
     LessThan,
     GreaterThan,
+    LessEqual,
+    GreaterEqual,
+    Equal,
+    NotEqual,
     Assign,
 }
 
@@ -17,6 +21,10 @@ impl InfixOp {
             Self::Assign => 0,
             Self::LessThan => 1,
             Self::GreaterThan => 1,
+            Self::LessEqual => 1,
+            Self::GreaterEqual => 1,
+            Self::Equal => 1,
+            Self::NotEqual => 1,
             Self::Add => 2,
             Self::Sub => 3,
             Self::Mul => 4,
@@ -31,6 +39,10 @@ impl InfixOp {
             Self::Div => "/",
             Self::LessThan => "<",
             Self::GreaterThan => ">",
+            Self::LessEqual => "<=",
+            Self::GreaterEqual => ">=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
             Self::Assign => "=",
         }
     }
@@ -42,6 +54,10 @@ impl InfixOp {
             Self::Div => true,
             Self::LessThan => true,
             Self::GreaterThan => true,
+            Self::LessEqual => true,
+            Self::GreaterEqual => true,
+            Self::Equal => true,
+            Self::NotEqual => true,
             Self::Assign => true,
         }
     }
@@ -61,6 +77,10 @@ impl InfixOp {
         Some(match symbol {
             Symbol::OpenAngle => Self::LessThan,
             Symbol::CloseAngle => Self::GreaterThan,
+            Symbol::LessEquals => Self::LessEqual,
+            Symbol::GreaterEquals => Self::GreaterEqual,
+            Symbol::DoubleEquals => Self::Equal,
+            Symbol::BangEquals => Self::NotEqual,
             Symbol::Plus => Self::Add,
             Symbol::Minus => Self::Sub,
             Symbol::Asterisk => Self::Mul,
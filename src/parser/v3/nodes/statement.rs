@@ -1,10 +1,10 @@
 use super::{
-    Keyword, Node, PExpr, PFunction, PIdent, PStruct, PVarDef, Parsable, ParseResult, ParserCtx,
-    Symbol, Token,
+    parse_attrs, CompilerMsg, Keyword, Node, PAttr, PExpr, PFunction, PImpl, PModDef, PStruct,
+    PTrait, PTypeAlias, PUseTree, PVarDef, Parsable, ParseResult, ParserCtx, Symbol, Token,
 };
 
 pub enum PStatement {
-    Let(Node<PVarDef>, Node<PExpr>),
+    Let(Node<PVarDef>, Option<Node<PExpr>>),
     Return(Option<Node<PExpr>>),
     Expr(Node<PExpr>),
 }
@@ -12,12 +12,31 @@ pub enum PStatement {
 pub enum PConstStatement {
     Fn(Node<PFunction>),
     Struct(Node<PStruct>),
-    Import(Node<PIdent>),
+    Type(Node<PTypeAlias>),
+    Trait(Node<PTrait>),
+    Impl(Node<PImpl>),
+    Import(Node<PUseTree>),
+    Mod(Node<PModDef>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PVisibility {
+    Private,
+    Public,
 }
 
 pub enum PStatementLike {
-    Statement(PStatement),
-    Const(PConstStatement),
+    Statement(Vec<Node<PAttr>>, PStatement),
+    Const(PVisibility, PConstStatement),
+}
+
+impl PStatementLike {
+    fn item(c: PConstStatement) -> Self {
+        Self::Const(PVisibility::Private, c)
+    }
+    fn statement(s: PStatement) -> Self {
+        Self::Statement(Vec::new(), s)
+    }
 }
 
 impl Parsable for PStatementLike {
@@ -27,32 +46,97 @@ impl Parsable for PStatementLike {
             Token::Keyword(Keyword::Let) => {
                 ctx.next();
                 let def = ctx.parse()?;
-                ctx.expect_sym(Symbol::Equals)?;
+                // `let x: T;` is set later
+                if !ctx.peek().is_some_and(|t| t.is_symbol(Symbol::Equals)) {
+                    return ParseResult::Ok(Self::statement(PStatement::Let(def, None)));
+                }
+                ctx.next();
                 ctx.parse()
-                    .map_res(|expr| Self::Statement(PStatement::Let(def, expr)))
+                    .map_res(|expr| Self::statement(PStatement::Let(def, Some(expr))))
             }
             Token::Keyword(Keyword::Return) => {
                 ctx.next();
                 if ctx.peek().is_some_and(|t| t.is_symbol(Symbol::Semicolon)) {
-                    ParseResult::Ok(Self::Statement(PStatement::Return(None)))
+                    ParseResult::Ok(Self::statement(PStatement::Return(None)))
                 } else {
                     ctx.parse()
-                        .map_res(|res| Self::Statement(PStatement::Return(Some(res))))
+                        .map_res(|res| Self::statement(PStatement::Return(Some(res))))
                 }
             }
+            Token::Keyword(Keyword::Pub) => {
+                let span = next.span;
+                ctx.next();
+                match Self::parse(ctx)? {
+                    Self::Const(_, c) => ParseResult::Ok(Self::Const(PVisibility::Public, c)),
+                    s => {
+                        ctx.err(CompilerMsg::new(
+                            "Only items can be marked `pub`".to_string(),
+                            span,
+                        ));
+                        ParseResult::Recover(s)
+                    }
+                }
+            }
+            Token::Symbol(Symbol::Hash) => {
+                let span = next.span;
+                let attrs = parse_attrs(ctx);
+                let mut s = Self::parse(ctx)?;
+                match &mut s {
+                    Self::Statement(a, _) => *a = attrs,
+                    Self::Const(_, PConstStatement::Fn(f)) => {
+                        if let Some(f) = f.as_mut() {
+                            f.attrs = attrs;
+                        }
+                    }
+                    Self::Const(_, PConstStatement::Struct(st)) => {
+                        if let Some(st) = st.as_mut() {
+                            st.attrs = attrs;
+                        }
+                    }
+                    Self::Const(_, PConstStatement::Mod(m)) => {
+                        if let Some(m) = m.as_mut() {
+                            m.attrs = attrs;
+                        }
+                    }
+                    _ => ctx.err(CompilerMsg::new(
+                        "Attributes can only be put on functions, structs, modules and statements"
+                            .to_string(),
+                        span,
+                    )),
+                }
+                ParseResult::Ok(s)
+            }
             Token::Keyword(Keyword::Fn) => {
                 ctx.next();
-                ParseResult::Ok(Self::Const(PConstStatement::Fn(ctx.parse()?)))
+                ParseResult::Ok(Self::item(PConstStatement::Fn(ctx.parse()?)))
             }
             Token::Keyword(Keyword::Struct) => {
                 ctx.next();
-                ParseResult::Ok(Self::Const(PConstStatement::Struct(ctx.parse()?)))
+                ParseResult::Ok(Self::item(PConstStatement::Struct(ctx.parse()?)))
+            }
+            Token::Keyword(Keyword::Type) => {
+                ctx.next();
+                ParseResult::Ok(Self::item(PConstStatement::Type(ctx.parse()?)))
+            }
+            Token::Keyword(Keyword::Trait) => {
+                ctx.next();
+                ParseResult::Ok(Self::item(PConstStatement::Trait(ctx.parse()?)))
+            }
+            Token::Keyword(Keyword::Impl) => {
+                ctx.next();
+                ParseResult::Ok(Self::item(PConstStatement::Impl(ctx.parse()?)))
             }
             Token::Keyword(Keyword::Import) => {
                 ctx.next();
-                ParseResult::Ok(Self::Const(PConstStatement::Import(ctx.parse()?)))
+                ParseResult::Ok(Self::item(PConstStatement::Import(ctx.parse()?)))
             }
-            _ => ctx.parse().map_res(|n| Self::Statement(PStatement::Expr(n))),
+            Token::Keyword(Keyword::Mod) => {
+                ctx.next();
+                ParseResult::Ok(Self::item(PConstStatement::Mod(ctx.parse()?)))
+            }
+            _ => ctx
+                .parse()
+                .map_res(|n| Self::statement(PStatement::Expr(n))),
         }
     }
 }
@@ -63,8 +147,10 @@ impl std::fmt::Debug for PStatement {
             PStatement::Let(n, e) => {
                 f.write_str("let ")?;
                 n.fmt(f)?;
-                f.write_str(" = ")?;
-                e.fmt(f)?;
+                if let Some(e) = e {
+                    f.write_str(" = ")?;
+                    e.fmt(f)?;
+                }
             }
             PStatement::Return(e) => {
                 f.write_str("return ")?;
@@ -86,9 +172,21 @@ impl std::fmt::Debug for PConstStatement {
             Self::Struct(s) => {
                 s.fmt(f)?;
             }
+            Self::Type(t) => {
+                t.fmt(f)?;
+            }
+            Self::Trait(t) => {
+                t.fmt(f)?;
+            }
+            Self::Impl(i) => {
+                i.fmt(f)?;
+            }
             Self::Import(s) => {
                 writeln!(f, "import {:?}", s);
             }
+            Self::Mod(m) => {
+                m.fmt(f)?;
+            }
         }
         Ok(())
     }
@@ -97,8 +195,17 @@ impl std::fmt::Debug for PConstStatement {
 impl std::fmt::Debug for PStatementLike {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Statement(s) => s.fmt(f),
-            Self::Const(c) => c.fmt(f),
+            Self::Statement(attrs, s) => {
+                for a in attrs {
+                    writeln!(f, "{a:?}")?;
+                }
+                s.fmt(f)
+            }
+            Self::Const(PVisibility::Private, c) => c.fmt(f),
+            Self::Const(PVisibility::Public, c) => {
+                f.write_str("pub ")?;
+                c.fmt(f)
+            }
         }
     }
 }
@@ -1,41 +1,221 @@
 use crate::{
-    common::CompilerMsg,
+    common::{CompilerMsg, FilePos},
     parser::{Parsable, ParseResult},
 };
 
+use super::{ParserCtx, Symbol, Token};
+
+/// `"..."` or `r#"..."#`; parsing starts with the opening quote (or the `r`) as the next token
 pub struct PString(pub String);
 
+/// `b"..."` or `br#"..."#`; `\xNN` can be any byte here
+pub struct PByteString(pub Vec<u8>);
+
+/// what a literal holds, which decides the escapes it can have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrKind {
+    Str,
+    Bytes,
+    Char,
+}
+
+/// what an escape turned into
+pub enum Escaped {
+    Char(char),
+    Byte(u8),
+    /// `\` at the end of a line, which skips the newline and the indent after it
+    Skip,
+}
+
+/// the kind of string literal coming up, if any. prefixes have to be right up against the
+/// quote, so the char after the prefix word decides it
+pub fn peek_string(ctx: &mut ParserCtx) -> Option<StrKind> {
+    let Token::Word(word) = &ctx.peek()?.token else {
+        return ctx
+            .peek()?
+            .is_symbol(Symbol::DoubleQuote)
+            .then_some(StrKind::Str);
+    };
+    let (kind, raw) = match word.as_str() {
+        "r" => (StrKind::Str, true),
+        "b" => (StrKind::Bytes, false),
+        "br" => (StrKind::Bytes, true),
+        _ => return None,
+    };
+    match ctx.chars().peek()? {
+        '"' => Some(kind),
+        '#' if raw => Some(kind),
+        _ => None,
+    }
+}
+
 impl Parsable for PString {
-    fn parse(ctx: &mut crate::parser::ParserCtx) -> ParseResult<Self> {
-        let cursor = ctx.cursor.chars();
-        let mut str = String::new();
-        loop {
-            let c = cursor.expect_next()?;
-            if c == '"' {
-                return ParseResult::Ok(Self(str));
-            }
-            str.push(match c {
-                '\\' => {
-                    let start = cursor.prev_pos();
-                    let next = cursor.expect_next()?;
-                    match next {
-                        '"' => '"',
-                        '\'' => '\'',
-                        't' => '\t',
-                        'n' => '\n',
-                        '0' => '\0',
-                        other => {
-                            let end = cursor.prev_pos();
-                            ctx.output.err(CompilerMsg {
-                                msg: format!("Unknown escape sequence '\\{}'", other),
-                                spans: vec![start.to(end)],
-                            });
-                            other
-                        }
-                    }
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let content = parse_quoted(ctx, StrKind::Str)?;
+        let s = String::from_utf8(content).expect("string escapes only give utf-8");
+        ParseResult::Ok(Self(s))
+    }
+}
+
+impl Parsable for PByteString {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        ParseResult::Ok(Self(parse_quoted(ctx, StrKind::Bytes)?))
+    }
+}
+
+fn parse_quoted(ctx: &mut ParserCtx, kind: StrKind) -> Result<Vec<u8>, CompilerMsg> {
+    let next = ctx.expect_peek()?.clone();
+    // for raw strings, how many `#`s have to follow the closing quote
+    let raw = match &next.token {
+        Token::Symbol(Symbol::DoubleQuote) => None,
+        Token::Word(w) if w.ends_with('r') => {
+            let chars = ctx.chars();
+            let mut hashes = 0;
+            while chars.peek() == Some('#') {
+                chars.advance();
+                hashes += 1;
+            }
+            chars.expect('"')?;
+            Some(hashes)
+        }
+        Token::Word(_) => {
+            ctx.chars().expect('"')?;
+            None
+        }
+        _ => return Err(CompilerMsg::unexpected_token(&next, "a string")),
+    };
+    let mut content = Vec::new();
+    loop {
+        let c = ctx.chars().expect_next()?;
+        if c == '"' {
+            let Some(hashes) = raw else {
+                break;
+            };
+            let chars = ctx.chars();
+            let mut n = 0;
+            while n < hashes && chars.peek() == Some('#') {
+                chars.advance();
+                n += 1;
+            }
+            if n == hashes {
+                break;
+            }
+            content.push(b'"');
+            content.extend(std::iter::repeat_n(b'#', n));
+            continue;
+        }
+        if c == '\\' && raw.is_none() {
+            let start = ctx.chars().prev_pos();
+            match escape(ctx, start, kind)? {
+                Escaped::Char(c) => {
+                    content.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes())
                 }
-                _ => c,
-            })
+                Escaped::Byte(b) => content.push(b),
+                Escaped::Skip => (),
+            }
+            continue;
+        }
+        if kind == StrKind::Bytes && !c.is_ascii() {
+            let pos = ctx.chars().prev_pos();
+            ctx.err(CompilerMsg::at(
+                pos,
+                "Byte strings can only have ASCII characters; use '\\x' escapes".to_string(),
+            ));
+        }
+        content.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+    // the chars were read past the quote token, so this moves on to whatever's after the string
+    ctx.next();
+    Ok(content)
+}
+
+/// reads an escape after its `\`, which is at `start`. bad escapes are reported with a span
+/// over the escape and turned into something close so parsing can keep going
+pub fn escape(ctx: &mut ParserCtx, start: FilePos, kind: StrKind) -> Result<Escaped, CompilerMsg> {
+    let c = ctx.chars().expect_next()?;
+    let err = |ctx: &mut ParserCtx, msg: String| {
+        let span = start.to(ctx.chars().prev_pos());
+        ctx.err(CompilerMsg::new(msg, span));
+    };
+    Ok(Escaped::Char(match c {
+        '"' => '"',
+        '\'' => '\'',
+        '\\' => '\\',
+        't' => '\t',
+        'n' => '\n',
+        'r' => '\r',
+        '0' => '\0',
+        '\n' | '\r' if kind != StrKind::Char => {
+            ctx.chars().skip_whitespace();
+            return Ok(Escaped::Skip);
+        }
+        'x' => {
+            let digits = hex_digits(ctx, 2);
+            if digits.len() != 2 {
+                err(ctx, "Expected two hex digits after '\\x'".to_string());
+                return Ok(Escaped::Byte(0));
+            }
+            let b = u8::from_str_radix(&digits, 16).expect("checked digits");
+            if kind != StrKind::Bytes && !b.is_ascii() {
+                err(
+                    ctx,
+                    format!("'\\x{digits}' is out of range; use '\\u{{{digits}}}' for non-ASCII"),
+                );
+                b as char
+            } else {
+                return Ok(Escaped::Byte(b));
+            }
         }
+        'u' => {
+            if ctx.chars().peek() != Some('{') {
+                err(ctx, "Expected '{' after '\\u'".to_string());
+                return Ok(Escaped::Char(char::REPLACEMENT_CHARACTER));
+            }
+            ctx.chars().advance();
+            let digits = hex_digits(ctx, 6);
+            if ctx.chars().peek() != Some('}') {
+                err(
+                    ctx,
+                    "Expected up to 6 hex digits and '}' after '\\u{'".to_string(),
+                );
+                return Ok(Escaped::Char(char::REPLACEMENT_CHARACTER));
+            }
+            ctx.chars().advance();
+            if kind == StrKind::Bytes {
+                err(
+                    ctx,
+                    "Unicode escapes can't be used in byte strings".to_string(),
+                );
+                return Ok(Escaped::Byte(0));
+            }
+            let c = u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32);
+            match c {
+                Some(c) => c,
+                None => {
+                    err(ctx, format!("'\\u{{{digits}}}' is not a valid char"));
+                    char::REPLACEMENT_CHARACTER
+                }
+            }
+        }
+        other => {
+            err(ctx, format!("Unknown escape sequence '\\{other}'"));
+            other
+        }
+    }))
+}
+
+/// up to `max` hex digits, stopping before anything else
+fn hex_digits(ctx: &mut ParserCtx, max: usize) -> String {
+    let chars = ctx.chars();
+    let mut digits = String::new();
+    while digits.len() < max
+        && let Some(c) = chars.peek()
+        && c.is_ascii_hexdigit()
+    {
+        digits.push(c);
+        chars.advance();
     }
+    digits
 }
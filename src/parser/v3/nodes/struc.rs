@@ -3,8 +3,8 @@ use std::fmt::{Debug, Write};
 use crate::{parser::ParsableWith, util::Padder};
 
 use super::{
-    util::parse_list, CompilerMsg, Node, PFieldDef, PGenericDef, PIdent, PType, PVarDef, Parsable,
-    ParseResult, ParserCtx, Symbol,
+    parse_attrs, util::parse_list, CompilerMsg, Keyword, Node, PAttr, PFieldDef, PGenericDef,
+    PIdent, PType, PVisibility, Parsable, ParseResult, ParserCtx, Symbol,
 };
 
 #[derive(Debug)]
@@ -12,13 +12,24 @@ pub struct PStruct {
     pub name: Node<PIdent>,
     pub generics: Vec<Node<PGenericDef>>,
     pub fields: PStructFields,
+    /// `struct Name: copy`
+    pub copy: bool,
+    pub attrs: Vec<Node<PAttr>>,
 }
 
 pub struct PMap(pub Vec<Node<PFieldDef>>);
 
+#[derive(Debug)]
+pub struct PStructField {
+    pub vis: PVisibility,
+    pub name: Node<PIdent>,
+    pub ty: Node<PType>,
+    pub attrs: Vec<Node<PAttr>>,
+}
+
 #[derive(Debug)]
 pub enum PStructFields {
-    Named(Vec<Node<PVarDef>>),
+    Named(Vec<Node<PStructField>>),
     Tuple(Vec<Node<PType>>),
     None,
 }
@@ -35,6 +46,22 @@ impl Parsable for PStruct {
         } else {
             Vec::new()
         };
+        let mut copy = false;
+        if next.is_symbol(Symbol::Colon) {
+            ctx.next();
+            let marker: Node<PIdent> = ctx.parse()?;
+            if let Some(m) = marker.as_ref()
+                && m.0 == "copy"
+            {
+                copy = true;
+            } else {
+                ctx.err(CompilerMsg::new(
+                    "Expected `copy` after `:` in a struct".to_string(),
+                    marker.origin,
+                ));
+            }
+            next = ctx.expect_peek()?;
+        }
         let fields = if next.is_symbol(Symbol::Semicolon) {
             ctx.next();
             PStructFields::None
@@ -51,12 +78,36 @@ impl Parsable for PStruct {
                 name,
                 generics: args,
                 fields: PStructFields::None,
+                copy,
+                attrs: Vec::new(),
             });
         };
         ParseResult::Ok(PStruct {
             name,
             generics: args,
             fields,
+            copy,
+            attrs: Vec::new(),
+        })
+    }
+}
+
+impl Parsable for PStructField {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let attrs = parse_attrs(ctx);
+        let vis = if ctx.expect_peek()?.is_keyword(Keyword::Pub) {
+            ctx.next();
+            PVisibility::Public
+        } else {
+            PVisibility::Private
+        };
+        let name = ctx.parse()?;
+        ctx.expect_sym(Symbol::Colon)?;
+        ctx.parse().map_res(|ty| Self {
+            vis,
+            name,
+            ty,
+            attrs,
         })
     }
 }
@@ -1,6 +1,6 @@
 use super::{
-    util::{parse_list, parse_list_nosep},
-    PFunction, PFunctionHeader, PIdent, Keyword, Node, Parsable, ParserCtx, Symbol, PType,
+    CompilerMsg, Keyword, Node, PFunction, PFunctionHeader, PIdent, PType, Parsable, ParseResult,
+    ParserCtx, Symbol,
 };
 
 #[derive(Debug)]
@@ -17,23 +17,45 @@ pub struct PImpl {
 }
 
 impl Parsable for PTrait {
-    fn parse(ctx: &mut ParserCtx) -> super::ParseResult<Self> {
-        ctx.expect_kw(Keyword::Trait)?;
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
         let name = ctx.parse()?;
         ctx.expect_sym(Symbol::OpenCurly)?;
-        let fns = parse_list(ctx, Symbol::CloseCurly)?;
-        super::ParseResult::Ok(Self { name, fns })
+        let mut fns = Vec::new();
+        while !ctx.expect_peek()?.is_symbol(Symbol::CloseCurly) {
+            ctx.expect_kw(Keyword::Fn)?;
+            let res = ctx.parse();
+            fns.push(res.node);
+            if res.recover {
+                ctx.seek_syms(&[Symbol::Semicolon, Symbol::CloseCurly]);
+                if ctx.peek().is_none() {
+                    return ParseResult::Err(CompilerMsg::unexpected_end());
+                }
+            }
+            if ctx.expect_peek()?.is_symbol(Symbol::Semicolon) {
+                ctx.next();
+            }
+        }
+        ctx.expect_sym(Symbol::CloseCurly)?;
+        ParseResult::Ok(Self { name, fns })
     }
 }
 
 impl Parsable for PImpl {
-    fn parse(ctx: &mut ParserCtx) -> super::ParseResult<Self> {
-        ctx.expect_kw(Keyword::Impl)?;
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
         let trait_ = ctx.parse()?;
         ctx.expect_kw(Keyword::For)?;
         let for_ = ctx.parse()?;
         ctx.expect_sym(Symbol::OpenCurly)?;
-        let fns = parse_list_nosep(ctx, Symbol::CloseCurly)?;
-        super::ParseResult::Ok(Self { trait_, for_, fns })
+        let mut fns = Vec::new();
+        while !ctx.expect_peek()?.is_symbol(Symbol::CloseCurly) {
+            ctx.expect_kw(Keyword::Fn)?;
+            let res = ctx.parse();
+            fns.push(res.node);
+            if res.recover {
+                ctx.seek_sym(Symbol::CloseCurly);
+            }
+        }
+        ctx.expect_sym(Symbol::CloseCurly)?;
+        ParseResult::Ok(Self { trait_, for_, fns })
     }
 }
@@ -1,6 +1,11 @@
 use std::fmt::Debug;
 
-use super::{util::parse_list, Node, PIdent, Parsable, ParseResult, ParserCtx, Symbol};
+use crate::ir::Len;
+
+use super::{
+    util::{parse_len, parse_list},
+    Keyword, Node, PIdent, Parsable, ParseResult, ParserCtx, Symbol,
+};
 
 type BoxNode = Node<Box<PType>>;
 
@@ -8,6 +13,9 @@ pub enum PType {
     Member(BoxNode, Node<PIdent>),
     Ref(BoxNode),
     Generic(BoxNode, Vec<Node<PType>>),
+    Dyn(BoxNode),
+    /// `[T; N]`
+    Array(BoxNode, Len),
     Ident(PIdent),
 }
 
@@ -15,10 +23,40 @@ pub struct PGenericDef {
     pub name: Node<PIdent>,
 }
 
+#[derive(Debug)]
+pub struct PTypeAlias {
+    pub name: Node<PIdent>,
+    pub generics: Vec<Node<PGenericDef>>,
+    pub ty: Node<PType>,
+}
+
 impl Parsable for PType {
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
         let start = ctx.next_start();
-        let mut cur = ctx.parse()?.map(PType::Ident);
+        let next = ctx.expect_peek()?;
+        let dyn_ = next.is_keyword(Keyword::Dyn);
+        let mut cur = if next.is_symbol(Symbol::OpenSquare) {
+            ctx.next();
+            let ty = ctx.parse()?.bx();
+            ctx.expect_sym(Symbol::Semicolon)?;
+            let len = parse_len(ctx)?;
+            ctx.expect_sym(Symbol::CloseSquare)?;
+            Node::new(PType::Array(ty, len), start.to(ctx.prev_end()))
+        } else {
+            if dyn_ {
+                ctx.next();
+            }
+            ctx.parse()?.map(PType::Ident)
+        };
+        if dyn_ {
+            // only the trait path belongs to dyn; `dyn Trait&` is a ref to a trait object
+            while ctx.peek().is_some_and(|n| n.is_symbol(Symbol::DoubleColon)) {
+                ctx.next();
+                let mem = ctx.parse()?;
+                cur = Node::new(PType::Member(cur.bx(), mem), start.to(ctx.prev_end()));
+            }
+            cur = Node::new(PType::Dyn(cur.bx()), start.to(ctx.prev_end()));
+        }
         loop {
             let span = start.to(ctx.prev_end());
             let Some(next) = ctx.peek() else {
@@ -44,6 +82,20 @@ impl Parsable for PType {
     }
 }
 
+impl Parsable for PTypeAlias {
+    fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
+        let name = ctx.parse()?;
+        let generics = if ctx.expect_peek()?.is_symbol(Symbol::OpenAngle) {
+            ctx.next();
+            parse_list(ctx, Symbol::CloseAngle)?
+        } else {
+            Vec::new()
+        };
+        ctx.expect_sym(Symbol::Equals)?;
+        ctx.parse().map_res(|ty| Self { name, generics, ty })
+    }
+}
+
 impl Parsable for PGenericDef {
     fn parse(ctx: &mut ParserCtx) -> ParseResult<Self> {
         ParseResult::Ok(Self { name: ctx.parse()? })
@@ -56,6 +108,8 @@ impl Debug for PType {
             PType::Member(node, name) => write!(f, "{:?}.{:?}", node, name)?,
             PType::Ref(node) => write!(f, "{:?}&", node)?,
             PType::Generic(node, args) => write!(f, "{:?}<{:?}>", node, args)?,
+            PType::Dyn(node) => write!(f, "dyn {:?}", node)?,
+            PType::Array(node, len) => write!(f, "[{:?}; {}]", node, len)?,
             PType::Ident(node) => node.fmt(f)?,
         }
         Ok(())
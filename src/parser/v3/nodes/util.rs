@@ -1,4 +1,17 @@
-use super::{Node, Parsable, ParserCtx, CompilerMsg, Symbol};
+use crate::ir::Len;
+
+use super::{Node, Parsable, ParserCtx, CompilerMsg, Symbol, Token};
+
+/// a length known at compile time, like in `[T; 4]`
+pub fn parse_len(ctx: &mut ParserCtx) -> Result<Len, CompilerMsg> {
+    let next = ctx.expect_next()?;
+    if let Token::Word(word) = &next.token
+        && let Ok(len) = word.parse::<Len>()
+    {
+        return Ok(len);
+    }
+    Err(CompilerMsg::unexpected_token(&next, "a length"))
+}
 
 pub fn parse_list_sep<T: Parsable>(
     ctx: &mut ParserCtx,
@@ -1,18 +1,31 @@
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Keyword {
     Fn,
+    Pub,
     Let,
     If,
     Return,
     Loop,
+    While,
     Break,
     Continue,
     Struct,
+    Type,
     Trait,
     Impl,
+    Dyn,
     For,
+    In,
     Asm,
     Import,
+    Mod,
+    As,
+    Super,
+    Crate,
+    SizeOf,
+    AlignOf,
+    True,
+    False,
     Funne,
 }
 
@@ -20,18 +33,31 @@ impl Keyword {
     pub fn from_string(str: &str) -> Option<Self> {
         Some(match str {
             "fn" => Self::Fn,
+            "pub" => Self::Pub,
             "struct" => Self::Struct,
+            "type" => Self::Type,
             "let" => Self::Let,
             "if" => Self::If,
             "for" => Self::For,
+            "in" => Self::In,
             "return" => Self::Return,
             "break" => Self::Break,
             "continue" => Self::Continue,
             "loop" => Self::Loop,
+            "while" => Self::While,
             "trait" => Self::Trait,
             "impl" => Self::Impl,
+            "dyn" => Self::Dyn,
             "asm" => Self::Asm,
             "import" => Self::Import,
+            "mod" => Self::Mod,
+            "as" => Self::As,
+            "super" => Self::Super,
+            "crate" => Self::Crate,
+            "size_of" => Self::SizeOf,
+            "align_of" => Self::AlignOf,
+            "true" => Self::True,
+            "false" => Self::False,
             "funne" => Self::Funne,
             _ => return None,
         })
@@ -9,6 +9,9 @@ pub enum Symbol {
     DoubleColon,
     Equals,
     DoubleEquals,
+    BangEquals,
+    LessEquals,
+    GreaterEquals,
     Arrow,
     DoubleArrow,
     Plus,
@@ -99,6 +102,18 @@ impl Symbol {
                 '/' => Self::DoubleSlash,
                 _ => return,
             },
+            Self::Bang => match next {
+                '=' => Self::BangEquals,
+                _ => return,
+            },
+            Self::OpenAngle => match next {
+                '=' => Self::LessEquals,
+                _ => return,
+            },
+            Self::CloseAngle => match next {
+                '=' => Self::GreaterEquals,
+                _ => return,
+            },
             Self::Ampersand => match next {
                 '&' => Self::DoubleAmpersand,
                 _ => return,
@@ -122,6 +137,9 @@ impl Symbol {
             Self::DoubleColon => "::",
             Self::Equals => "=",
             Self::DoubleEquals => "==",
+            Self::BangEquals => "!=",
+            Self::LessEquals => "<=",
+            Self::GreaterEquals => ">=",
             Self::Arrow => "->",
             Self::DoubleArrow => "=>",
             Self::Plus => "+",
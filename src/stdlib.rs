@@ -0,0 +1,17 @@
+//! the standard library sources, built into the compiler so no files are needed on disk
+
+/// (path from the std root without extension, source)
+pub const STD_FILES: &[(&str, &str)] = &[
+    ("lib", include_str!("../std/lib.lang")),
+    ("io", include_str!("../std/io.lang")),
+    ("char", include_str!("../std/char.lang")),
+    ("fmt", include_str!("../std/fmt.lang")),
+    ("heap", include_str!("../std/heap.lang")),
+    ("mem", include_str!("../std/mem.lang")),
+    ("ops", include_str!("../std/ops.lang")),
+    ("process", include_str!("../std/process.lang")),
+    ("string", include_str!("../std/string.lang")),
+    ("vec", include_str!("../std/vec.lang")),
+];
+
+pub const STD_NAME: &str = "std";